@@ -1,8 +1,13 @@
 use crate::geometry::{Scalar, Vect};
 
+use barneshut::BarnesHutTree;
+
+use crate::generators::seeded_rng;
 use crate::Graph;
 use itertools::Itertools;
-use std::collections::HashMap;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
 
@@ -10,21 +15,215 @@ const REPEL_CONST: Scalar = 2.0;
 const SPRING_CONST: Scalar = 1.0;
 const SPRING_LENGTH: Scalar = 2.0;
 const DELTA_T: Scalar = 0.99;
+const ITERATIONS: usize = 499;
+const THETA: Scalar = 0.5;
+
+pub mod barneshut;
+pub mod circular;
+pub mod crossing_reduction;
+pub mod fruchterman_reingold;
+pub mod incremental;
+pub mod kamada_kawai;
+pub mod metrics;
+pub mod packing;
+pub mod spectral;
+pub mod sugiyama;
+pub mod tree;
+
+// How the step size shrinks over the run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cooling {
+    None,
+    // falls linearly from delta_t to zero over the iterations.
+    Linear,
+    // multiplied by the factor every iteration.
+    Exponential(Scalar),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayoutAlgorithm {
+    // spring embedder: log springs between neighbours, repulsion between all other pairs.
+    Eades,
+    // ideal distance spring_length, moves are capped by a temperature that cools linearly.
+    FruchtermanReingold { temperature: Scalar },
+    // stress minimisation towards graph distances scaled by spring_length.
+    KamadaKawai,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutConfig<ID: Clone + Hash + Eq> {
+    pub iterations: usize,
+    pub repel_const: Scalar,
+    pub spring_const: Scalar,
+    pub spring_length: Scalar,
+    pub delta_t: Scalar,
+    pub cooling: Cooling,
+    // stop early once no node moves further than this in an iteration.
+    pub tolerance: Scalar,
+    pub seed: Option<u64>,
+    pub initial_range: (Scalar, Scalar),
+    pub initial_positions: Option<HashMap<ID, Vect>>,
+    // nodes that keep their initial position.
+    pub pinned: HashSet<ID>,
+    pub use_z: bool,
+    // Barnes–Hut opening angle for the repulsion, 0 computes every pair exactly.
+    pub theta: Scalar,
+    pub algorithm: LayoutAlgorithm,
+}
+
+impl<ID: Clone + Hash + Eq> Default for LayoutConfig<ID> {
+    fn default() -> Self {
+        Self {
+            iterations: ITERATIONS,
+            repel_const: REPEL_CONST,
+            spring_const: SPRING_CONST,
+            spring_length: SPRING_LENGTH,
+            delta_t: DELTA_T,
+            cooling: Cooling::None,
+            tolerance: 0.0,
+            seed: None,
+            initial_range: (300., 301.),
+            initial_positions: None,
+            pinned: HashSet::new(),
+            use_z: false,
+            theta: THETA,
+            algorithm: LayoutAlgorithm::Eades,
+        }
+    }
+}
+
+impl<ID: Clone + Hash + Eq> LayoutConfig<ID> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+    pub fn repel_const(mut self, c: Scalar) -> Self {
+        self.repel_const = c;
+        self
+    }
+    pub fn spring_const(mut self, c: Scalar) -> Self {
+        self.spring_const = c;
+        self
+    }
+    pub fn spring_length(mut self, l: Scalar) -> Self {
+        self.spring_length = l;
+        self
+    }
+    pub fn delta_t(mut self, dt: Scalar) -> Self {
+        self.delta_t = dt;
+        self
+    }
+    pub fn cooling(mut self, cooling: Cooling) -> Self {
+        self.cooling = cooling;
+        self
+    }
+    pub fn tolerance(mut self, tolerance: Scalar) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    pub fn initial_range(mut self, min: Scalar, max: Scalar) -> Self {
+        self.initial_range = (min, max);
+        self
+    }
+    pub fn initial_positions(mut self, positions: HashMap<ID, Vect>) -> Self {
+        self.initial_positions = Some(positions);
+        self
+    }
+    pub fn pinned(mut self, pinned: HashSet<ID>) -> Self {
+        self.pinned = pinned;
+        self
+    }
+    pub fn three_d(mut self, use_z: bool) -> Self {
+        self.use_z = use_z;
+        self
+    }
+    pub fn theta(mut self, theta: Scalar) -> Self {
+        self.theta = theta;
+        self
+    }
+    pub fn algorithm(mut self, algorithm: LayoutAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    fn step(&self, iteration: usize) -> Scalar {
+        match self.cooling {
+            Cooling::None => self.delta_t,
+            Cooling::Linear => {
+                self.delta_t * (1.0 - iteration as Scalar / self.iterations.max(1) as Scalar)
+            }
+            Cooling::Exponential(f) => self.delta_t * f.powi(iteration as i32),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutStats {
+    pub iterations: usize,
+    pub converged: bool,
+    // largest distance a node moved in the last iteration.
+    pub max_displacement: Scalar,
+}
+
+#[derive(Debug, Clone)]
+pub struct LayoutResult<ID: Clone + Hash + Eq> {
+    pub positions: HashMap<ID, Vect>,
+    pub stats: LayoutStats,
+}
 
 pub fn layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
     g: &Graph<T, E, ID>,
 ) -> HashMap<ID, Vect> {
-    let mut positions = create_initial_positions(g);
-    println!("{:?}", positions);
+    layout_with(g, &LayoutConfig::new()).positions
+}
+
+pub fn layout_with<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    config: &LayoutConfig<ID>,
+) -> LayoutResult<ID> {
+    match config.algorithm {
+        LayoutAlgorithm::Eades => eades(g, config),
+        LayoutAlgorithm::FruchtermanReingold { temperature } => {
+            fruchterman_reingold::layout(g, config, temperature)
+        }
+        LayoutAlgorithm::KamadaKawai => kamada_kawai::layout(g, config),
+    }
+}
+
+fn eades<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    config: &LayoutConfig<ID>,
+) -> LayoutResult<ID> {
+    let mut positions = create_initial_positions(g, config);
+    let mut stats = LayoutStats {
+        iterations: 0,
+        converged: false,
+        max_displacement: 0.0,
+    };
 
-    for _i in 1..500 {
-        let repel_forces = calculate_repel_forces(g, &mut positions);
-        let spring_forces = calculate_spring_forces(g, &mut positions);
+    for i in 0..config.iterations {
+        let repel_forces = calculate_repel_forces(g, &mut positions, config);
+        let spring_forces = calculate_spring_forces(g, &mut positions, config);
         let resultant_forces = calculate_resultant_forces(&repel_forces, &spring_forces);
-        positions = update_positions(positions, resultant_forces);
+        let (new_positions, moved) =
+            update_positions(positions, resultant_forces, config.step(i), &config.pinned);
+        positions = new_positions;
+
+        stats.iterations = i + 1;
+        stats.max_displacement = moved;
+        if moved < config.tolerance {
+            stats.converged = true;
+            break;
+        }
     }
-    println!("=> {:?}", positions);
-    positions
+    LayoutResult { positions, stats }
 }
 pub fn connections<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
     positions: &HashMap<ID, Vect>,
@@ -42,44 +241,105 @@ pub fn connections<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
     }
     cons
 }
-fn repelling_force(pos_u: &Vect, pos_v: &Vect) -> Vect {
+// line segments of every edge, passing through its bend points when it has any.
+pub fn routed_connections<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    positions: &HashMap<ID, Vect>,
+    bends: &HashMap<ID, Vec<Vect>>,
+    g: &Graph<T, E, ID>,
+) -> Vec<(Vect, Vect)> {
+    routed_edges(positions, bends, g)
+        .into_iter()
+        .flat_map(|(_, points)| points.into_iter().tuple_windows::<(Vect, Vect)>())
+        .collect()
+}
+// every edge id with the points its route passes through, end points included, in id order.
+pub fn routed_edges<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    positions: &HashMap<ID, Vect>,
+    bends: &HashMap<ID, Vec<Vect>>,
+    g: &Graph<T, E, ID>,
+) -> Vec<(ID, Vec<Vect>)> {
+    let mut routes: Vec<(ID, Vec<Vect>)> = Vec::new();
+    for edge_id in g.edges.keys().sorted() {
+        let edge = g.edges.get(edge_id).unwrap();
+        let mut points = vec![*positions.get(&edge.left).unwrap()];
+        if let Some(b) = bends.get(edge_id) {
+            points.extend(b.iter().copied());
+        }
+        points.push(*positions.get(&edge.right).unwrap());
+        routes.push((*edge_id, points));
+    }
+    routes
+}
+fn repelling_force(pos_u: &Vect, pos_v: &Vect, repel_const: Scalar) -> Vect {
     //applies to node u and ALL other nodes
     //unit v in direction of u -> v   v-u
-    let unit_uv = (*pos_u - *pos_v).as_unit_vector();
+    //inverse square in the distance. coincident nodes have no direction to push in,
+    //so they exert nothing.
     let euc_dist = pos_u.euclid_distance(pos_v);
-    unit_uv.scalar_mul(REPEL_CONST / euc_dist * euc_dist)
+    if euc_dist == 0.0 {
+        return Vect::zero();
+    }
+    let unit_uv = (*pos_u - *pos_v).normalize_or_zero();
+    unit_uv.scalar_mul(repel_const / (euc_dist * euc_dist))
 }
-fn spring_force(pos_u: &Vect, pos_v: &Vect) -> Vect {
+fn spring_force(pos_u: &Vect, pos_v: &Vect, spring_const: Scalar, spring_length: Scalar) -> Vect {
     //applies to node u and all its immediate neighbours.
-    let unit_vu = (*pos_v - *pos_u).as_unit_vector();
     let euc_dist = pos_u.euclid_distance(pos_v);
+    if euc_dist == 0.0 {
+        return Vect::zero();
+    }
+    let unit_vu = (*pos_v - *pos_u).normalize_or_zero();
 
-    let x = (euc_dist / SPRING_LENGTH).ln();
-    unit_vu.scalar_mul(SPRING_CONST * x)
+    let x = (euc_dist / spring_length).ln();
+    unit_vu.scalar_mul(spring_const * x)
 }
-fn create_initial_positions<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+// nodes without a supplied position start at random, seeded when the config has a seed.
+pub(crate) fn create_initial_positions<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
     g: &Graph<T, E, ID>,
+    config: &LayoutConfig<ID>,
 ) -> HashMap<ID, Vect> {
+    let mut rng = match config.seed {
+        Some(seed) => seeded_rng(seed),
+        None => StdRng::from_entropy(),
+    };
+    let (min, max) = config.initial_range;
     let mut positions: HashMap<ID, Vect> = HashMap::new();
-    for node_id in g.nodes.keys() {
+    for node_id in g.nodes.keys().sorted() {
         //setup with initial vect.
-        positions.insert(*node_id, Vect::random(300., 301., false));
+        let given = config
+            .initial_positions
+            .as_ref()
+            .and_then(|p| p.get(node_id));
+        let v = match given {
+            Some(v) => *v,
+            None => Vect::random_from(&mut rng, min, max, config.use_z),
+        };
+        positions.insert(*node_id, v);
     }
     positions
 }
 
+// also returns the largest distance moved. Pinned nodes stay where they are.
 fn update_positions<ID: Debug + Copy + Ord + Clone + Hash + Eq>(
     positions: HashMap<ID, Vect>,
     resultant_forces: HashMap<ID, Vect>,
-) -> HashMap<ID, Vect> {
+    delta_t: Scalar,
+    pinned: &HashSet<ID>,
+) -> (HashMap<ID, Vect>, Scalar) {
     let mut new_positions: HashMap<ID, Vect> = HashMap::new();
+    let mut moved: Scalar = 0.0;
     for pos in positions.keys() {
         let old_pos = positions.get(pos).unwrap();
-        let f = resultant_forces.get(pos).unwrap().scalar_mul(DELTA_T);
+        if pinned.contains(pos) {
+            new_positions.insert(*pos, *old_pos);
+            continue;
+        }
+        let f = resultant_forces.get(pos).unwrap().scalar_mul(delta_t);
         let new_pos = *old_pos + f;
+        moved = moved.max(f.magnitude());
         new_positions.insert(*pos, new_pos);
     }
-    new_positions
+    (new_positions, moved)
 }
 
 fn calculate_resultant_forces<ID: Debug + Copy + Ord + Clone + Hash + Eq>(
@@ -98,14 +358,15 @@ fn calculate_resultant_forces<ID: Debug + Copy + Ord + Clone + Hash + Eq>(
 fn calculate_spring_forces<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
     g: &Graph<T, E, ID>,
     positions: &mut HashMap<ID, Vect>,
+    config: &LayoutConfig<ID>,
 ) -> HashMap<ID, Vect> {
     let mut spring_forces: HashMap<ID, Vect> = HashMap::new();
     for node_u in g.nodes.keys().sorted() {
         let mut spring_u = Vect::new(0., 0., 0.);
         let u = positions.get(node_u).unwrap();
-        for node_v in g.neighbors(*node_u).unwrap() {
+        for node_v in g.neighbors(*node_u).unwrap().into_iter().sorted() {
             let v = positions.get(&node_v).unwrap();
-            spring_u = spring_u + spring_force(u, v) - repelling_force(u, v)
+            spring_u += spring_force(u, v, config.spring_const, config.spring_length);
         }
         spring_forces.insert(*node_u, spring_u);
     }
@@ -115,17 +376,34 @@ fn calculate_spring_forces<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
 fn calculate_repel_forces<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
     g: &Graph<T, E, ID>,
     positions: &mut HashMap<ID, Vect>,
+    config: &LayoutConfig<ID>,
 ) -> HashMap<ID, Vect> {
-    //
+    let ids: Vec<ID> = g.nodes.keys().copied().sorted().collect();
+    let points: Vec<Vect> = ids.iter().map(|id| *positions.get(id).unwrap()).collect();
+    let force = |u: &Vect, v: &Vect| repelling_force(u, v, config.repel_const);
+
     let mut repel_forces: HashMap<ID, Vect> = HashMap::new();
-    for node_u in g.nodes.keys().sorted() {
-        let u = positions.get(node_u).unwrap();
-        let mut repel_u = Vect::new(0., 0., 0.);
-        for node_v in g.nodes.keys().sorted() {
-            if node_u != node_v {
-                let v = positions.get(node_v).unwrap();
-                repel_u = repel_u + repelling_force(u, v);
+    if config.theta > 0.0 {
+        let tree = BarnesHutTree::new(&points);
+        for (ix, node_u) in ids.iter().enumerate() {
+            repel_forces.insert(*node_u, tree.force_on(ix, config.theta, force));
+        }
+    } else {
+        for (ix, node_u) in ids.iter().enumerate() {
+            let mut repel_u = Vect::new(0., 0., 0.);
+            for (jx, v) in points.iter().enumerate() {
+                if ix != jx {
+                    repel_u += force(&points[ix], v);
+                }
             }
+            repel_forces.insert(*node_u, repel_u);
+        }
+    }
+    // neighbours are held by their spring instead.
+    for (ix, node_u) in ids.iter().enumerate() {
+        let mut repel_u = *repel_forces.get(node_u).unwrap();
+        for node_v in g.neighbors(*node_u).unwrap().into_iter().sorted() {
+            repel_u -= force(&points[ix], positions.get(&node_v).unwrap());
         }
         repel_forces.insert(*node_u, repel_u);
     }
@@ -138,3 +416,6 @@ pub fn x() {
     let g: Graph<i32, i32, i32> = create_random_graph::<i32, i32, i32>(500, 1000, 1, 10, 0, 1);
     println!("{:?}", layout(&g));
 }
+
+#[cfg(test)]
+mod layouttests;
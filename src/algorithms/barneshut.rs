@@ -0,0 +1,152 @@
+use crate::geometry::{Scalar, Vect};
+
+// Barnes–Hut approximation of the all pairs repulsion. Points are bucketed into an octree
+// (a quadtree in practice when every z is 0, the upper z children just stay empty) and a
+// distant cell acts as a single body of its total mass at its centre of mass. A cell is
+// distant when size / distance < theta; theta = 0 makes the sum exact.
+const MAX_DEPTH: usize = 48;
+
+#[derive(Debug)]
+struct Cell {
+    centre: Vect,
+    half: Scalar,
+    mass: Scalar,
+    weighted: Vect,
+    bodies: Vec<usize>,
+    children: Option<[usize; 8]>,
+}
+
+impl Cell {
+    fn new(centre: Vect, half: Scalar) -> Self {
+        Self {
+            centre,
+            half,
+            mass: 0.0,
+            weighted: Vect::new(0., 0., 0.),
+            bodies: Vec::new(),
+            children: None,
+        }
+    }
+    fn octant(&self, p: &Vect) -> usize {
+        (p.x >= self.centre.x) as usize
+            | ((p.y >= self.centre.y) as usize) << 1
+            | ((p.z >= self.centre.z) as usize) << 2
+    }
+    fn contains(&self, p: &Vect) -> bool {
+        (p.x - self.centre.x).abs() <= self.half
+            && (p.y - self.centre.y).abs() <= self.half
+            && (p.z - self.centre.z).abs() <= self.half
+    }
+}
+
+#[derive(Debug)]
+pub struct BarnesHutTree {
+    cells: Vec<Cell>,
+    points: Vec<Vect>,
+}
+
+impl BarnesHutTree {
+    pub fn new(points: &[Vect]) -> Self {
+        let mut min = Vect::new(Scalar::MAX, Scalar::MAX, Scalar::MAX);
+        let mut max = Vect::new(Scalar::MIN, Scalar::MIN, Scalar::MIN);
+        for p in points {
+            min = Vect::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vect::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        let extent = max - min;
+        let half = extent.x.max(extent.y).max(extent.z) / 2.0 + 1e-9;
+        let centre = if points.is_empty() {
+            Vect::new(0., 0., 0.)
+        } else {
+            (min + max).scalar_mul(0.5)
+        };
+
+        let mut tree = Self {
+            cells: vec![Cell::new(centre, half)],
+            points: points.to_vec(),
+        };
+        for ix in 0..points.len() {
+            tree.insert(0, ix, 0);
+        }
+        tree
+    }
+
+    fn insert(&mut self, cell: usize, ix: usize, depth: usize) {
+        let p = self.points[ix];
+        let c = &mut self.cells[cell];
+        c.mass += 1.0;
+        c.weighted += p;
+        self.place(cell, ix, depth);
+    }
+
+    // puts the body into this cell's subtree, whose totals already include it.
+    fn place(&mut self, cell: usize, ix: usize, depth: usize) {
+        let p = self.points[ix];
+        if let Some(children) = self.cells[cell].children {
+            let k = self.cells[cell].octant(&p);
+            self.insert(children[k], ix, depth + 1);
+            return;
+        }
+        if self.cells[cell].bodies.is_empty() || depth >= MAX_DEPTH {
+            self.cells[cell].bodies.push(ix);
+            return;
+        }
+
+        // split the leaf and push its bodies down.
+        let Cell { centre, half, .. } = self.cells[cell];
+        let quarter = half / 2.0;
+        let mut children = [0; 8];
+        for (k, child) in children.iter_mut().enumerate() {
+            let offset = Vect::new(
+                if k & 1 != 0 { quarter } else { -quarter },
+                if k & 2 != 0 { quarter } else { -quarter },
+                if k & 4 != 0 { quarter } else { -quarter },
+            );
+            *child = self.cells.len();
+            self.cells.push(Cell::new(centre + offset, quarter));
+        }
+        self.cells[cell].children = Some(children);
+        let bodies = std::mem::take(&mut self.cells[cell].bodies);
+        for b in bodies {
+            self.place(cell, b, depth);
+        }
+        self.place(cell, ix, depth);
+    }
+
+    // total force on point ix, where force(p, q) is the force a unit body at q exerts on p.
+    pub fn force_on<F: Fn(&Vect, &Vect) -> Vect>(
+        &self,
+        ix: usize,
+        theta: Scalar,
+        force: F,
+    ) -> Vect {
+        let p = self.points[ix];
+        let mut total = Vect::new(0., 0., 0.);
+        let mut stack: Vec<usize> = vec![0];
+        while let Some(c) = stack.pop() {
+            let cell = &self.cells[c];
+            if cell.mass == 0.0 {
+                continue;
+            }
+            match cell.children {
+                None => {
+                    for b in cell.bodies.iter() {
+                        if *b != ix {
+                            total += force(&p, &self.points[*b]);
+                        }
+                    }
+                }
+                Some(children) => {
+                    let com = cell.weighted.scalar_mul(1.0 / cell.mass);
+                    let d = p.euclid_distance(&com);
+                    if !cell.contains(&p) && d > 0.0 && 2.0 * cell.half / d < theta {
+                        total += force(&p, &com).scalar_mul(cell.mass);
+                    } else {
+                        stack.extend(children.iter());
+                    }
+                }
+            }
+        }
+        total
+    }
+}
@@ -0,0 +1,161 @@
+use crate::geometry::{Scalar, Vect};
+use crate::Graph;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Nodes evenly spaced on a circle. The order decides how many chords cross; the heuristics
+// follow Baur & Brandes, "Crossing reduction in circular layouts" (2004).
+const SIFTING_ROUNDS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CircularOrdering {
+    ById,
+    // next is the node with most placed neighbours, ties go to a neighbour of the last node
+    // placed and then to fewer unplaced neighbours.
+    Connectivity,
+    // connectivity order, then every node moved to its best place on the circle.
+    Sifting,
+}
+
+// whether chords (a, b) and (c, d) between circle positions cross.
+fn chords_cross(a: usize, b: usize, c: usize, d: usize) -> bool {
+    if a == c || a == d || b == c || b == d {
+        return false;
+    }
+    let (lo, hi) = (a.min(b), a.max(b));
+    (lo < c && c < hi) != (lo < d && d < hi)
+}
+
+fn connectivity_order(adj: &[Vec<usize>]) -> Vec<usize> {
+    let n = adj.len();
+    let mut placed = vec![false; n];
+    let mut placed_neighbours = vec![0usize; n];
+    let mut order = Vec::with_capacity(n);
+    let mut last: Option<usize> = None;
+    for _ in 0..n {
+        let next = (0..n)
+            .filter(|v| !placed[*v])
+            .max_by_key(|v| {
+                let unplaced = adj[*v].len() - placed_neighbours[*v];
+                let follows = last.is_some_and(|l| adj[l].contains(v));
+                (
+                    placed_neighbours[*v],
+                    follows,
+                    std::cmp::Reverse(unplaced),
+                    std::cmp::Reverse(*v),
+                )
+            })
+            .unwrap();
+        placed[next] = true;
+        for w in adj[next].iter() {
+            placed_neighbours[*w] += 1;
+        }
+        order.push(next);
+        last = Some(next);
+    }
+    order
+}
+
+// Walks each node once around the circle by adjacent swaps. Swapping neighbours u and v
+// only flips the crossing state of chord pairs (v, x), (u, y) with four distinct ends, so
+// every step costs deg(u) * deg(v).
+fn sift(adj: &[Vec<usize>], order: &mut Vec<usize>) {
+    let n = order.len();
+    if n < 4 {
+        return;
+    }
+    for _ in 0..SIFTING_ROUNDS {
+        let mut improved = false;
+        for v in 0..n {
+            let mut seq: Vec<usize> = order.clone();
+            let start = seq.iter().position(|w| *w == v).unwrap();
+            seq.rotate_left(start);
+            let mut pos = vec![0; n];
+            for (i, w) in seq.iter().enumerate() {
+                pos[*w] = i;
+            }
+
+            let (mut change, mut best, mut best_at) = (0i64, 0i64, 0);
+            for i in 0..n - 1 {
+                let u = seq[i + 1];
+                for x in adj[v].iter().filter(|x| **x != u) {
+                    for y in adj[u].iter().filter(|y| **y != v && *y != x) {
+                        if chords_cross(pos[v], pos[*x], pos[u], pos[*y]) {
+                            change -= 1;
+                        } else {
+                            change += 1;
+                        }
+                    }
+                }
+                seq.swap(i, i + 1);
+                pos[v] = i + 1;
+                pos[u] = i;
+                if change < best {
+                    best = change;
+                    best_at = i + 1;
+                }
+            }
+            if best < 0 {
+                let mut seq: Vec<usize> = order.clone();
+                seq.rotate_left(start);
+                let moved = seq.remove(0);
+                seq.insert(best_at, moved);
+                *order = seq;
+                improved = true;
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+}
+
+pub fn circular_order<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    ordering: CircularOrdering,
+) -> Vec<ID> {
+    let ids: Vec<ID> = g.nodes.keys().copied().sorted().collect();
+    let index: HashMap<ID, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let adj: Vec<Vec<usize>> = ids
+        .iter()
+        .map(|id| {
+            let neighbours: HashSet<ID> = g.neighbors(*id).unwrap();
+            neighbours.iter().map(|w| index[w]).sorted().collect()
+        })
+        .collect();
+
+    let order = match ordering {
+        CircularOrdering::ById => (0..ids.len()).collect(),
+        CircularOrdering::Connectivity => connectivity_order(&adj),
+        CircularOrdering::Sifting => {
+            let mut order = connectivity_order(&adj);
+            sift(&adj, &mut order);
+            order
+        }
+    };
+    order.into_iter().map(|i| ids[i]).collect()
+}
+
+// first node at angle 0, the rest counter clockwise.
+pub fn circular_layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    radius: Scalar,
+    ordering: CircularOrdering,
+) -> HashMap<ID, Vect> {
+    let order = circular_order(g, ordering);
+    let step = 2.0 * PI / order.len().max(1) as Scalar;
+    order
+        .into_iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let angle = i as Scalar * step;
+            (
+                id,
+                Vect::new(radius * angle.cos(), radius * angle.sin(), 0.),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,81 @@
+use crate::algorithms::metrics::{edges_cross, segments};
+use crate::geometry::Vect;
+use crate::Graph;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Post-processing for any layout: two nodes trade places whenever that lowers the number of
+// edge crossings. The candidates are the end points of edges that cross each other; each
+// round tries every candidate pair once and the pass stops after a round that changes
+// nothing. Crossings are counted in x and y.
+
+// crossings with at least one edge in `touched`.
+fn local_crossings<ID: Copy + Eq + Hash>(
+    edges: &[(ID, ID)],
+    positions: &HashMap<ID, Vect>,
+    touched: &HashSet<usize>,
+) -> usize {
+    let mut count = 0;
+    for i in touched.iter() {
+        for (j, f) in edges.iter().enumerate() {
+            // pairs with both edges touched are seen twice, count them once.
+            if touched.contains(&j) && j <= *i {
+                continue;
+            }
+            if edges_cross(positions, edges[*i], *f) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+pub fn reduce_crossings<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    positions: &HashMap<ID, Vect>,
+    rounds: usize,
+) -> HashMap<ID, Vect> {
+    let mut positions = positions.clone();
+    let edges = segments(g);
+    let mut incident: HashMap<ID, Vec<usize>> = HashMap::new();
+    for (ix, (l, r)) in edges.iter().enumerate() {
+        incident.entry(*l).or_default().push(ix);
+        incident.entry(*r).or_default().push(ix);
+    }
+
+    for _ in 0..rounds {
+        let candidates: Vec<(ID, ID)> = edges
+            .iter()
+            .tuple_combinations()
+            .filter(|(e, f)| edges_cross(&positions, **e, **f))
+            .flat_map(|((a, b), (c, d))| [(*a, *c), (*a, *d), (*b, *c), (*b, *d)])
+            .map(|(u, v)| (u.min(v), u.max(v)))
+            .sorted()
+            .dedup()
+            .collect();
+        let mut improved = false;
+        for (u, v) in candidates {
+            let touched: HashSet<usize> = incident[&u]
+                .iter()
+                .chain(incident[&v].iter())
+                .copied()
+                .collect();
+            let before = local_crossings(&edges, &positions, &touched);
+            let (pu, pv) = (positions[&u], positions[&v]);
+            positions.insert(u, pv);
+            positions.insert(v, pu);
+            if local_crossings(&edges, &positions, &touched) < before {
+                improved = true;
+            } else {
+                positions.insert(u, pu);
+                positions.insert(v, pv);
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    positions
+}
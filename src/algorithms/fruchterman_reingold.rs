@@ -0,0 +1,95 @@
+use crate::algorithms::barneshut::BarnesHutTree;
+use crate::algorithms::{create_initial_positions, LayoutConfig, LayoutResult, LayoutStats};
+use crate::geometry::{Scalar, Vect};
+use crate::Graph;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Fruchterman & Reingold (1991). With ideal distance k every pair repels with k²/d and every
+// edge attracts with d²/k. The temperature caps how far a node moves and falls linearly to
+// zero, so the run settles by the last iteration.
+fn repulsion(u: &Vect, v: &Vect, k: Scalar) -> Vect {
+    let d = u.euclid_distance(v);
+    if d == 0.0 {
+        return Vect::new(0., 0., 0.);
+    }
+    (*u - *v).scalar_mul(k * k / (d * d))
+}
+
+fn attraction(u: &Vect, v: &Vect, k: Scalar) -> Vect {
+    let d = u.euclid_distance(v);
+    (*v - *u).scalar_mul(d / k)
+}
+
+pub(crate) fn layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    config: &LayoutConfig<ID>,
+    temperature: Scalar,
+) -> LayoutResult<ID> {
+    let mut positions = create_initial_positions(g, config);
+    let mut stats = LayoutStats {
+        iterations: 0,
+        converged: false,
+        max_displacement: 0.0,
+    };
+    let k = config.spring_length;
+    let ids: Vec<ID> = g.nodes.keys().copied().sorted().collect();
+    let edges: Vec<(ID, ID)> = g
+        .edges
+        .keys()
+        .sorted()
+        .map(|e| (g.edges[e].left, g.edges[e].right))
+        .filter(|(l, r)| l != r)
+        .collect();
+
+    for i in 0..config.iterations {
+        let points: Vec<Vect> = ids.iter().map(|id| positions[id]).collect();
+        let mut disp: HashMap<ID, Vect> = HashMap::new();
+        if config.theta > 0.0 {
+            let tree = BarnesHutTree::new(&points);
+            for (ix, id) in ids.iter().enumerate() {
+                disp.insert(
+                    *id,
+                    tree.force_on(ix, config.theta, |u, v| repulsion(u, v, k)),
+                );
+            }
+        } else {
+            for (ix, id) in ids.iter().enumerate() {
+                let mut d = Vect::new(0., 0., 0.);
+                for (jx, v) in points.iter().enumerate() {
+                    if ix != jx {
+                        d += repulsion(&points[ix], v, k);
+                    }
+                }
+                disp.insert(*id, d);
+            }
+        }
+        for (l, r) in edges.iter() {
+            let pull = attraction(&positions[l], &positions[r], k);
+            disp.insert(*l, disp[l] + pull);
+            disp.insert(*r, disp[r] - pull);
+        }
+
+        let t = temperature * (1.0 - i as Scalar / config.iterations as Scalar);
+        let mut moved: Scalar = 0.0;
+        for id in ids.iter().filter(|id| !config.pinned.contains(id)) {
+            let d = disp[id];
+            let length = d.magnitude();
+            if length > 0.0 {
+                let step = d.scalar_mul(length.min(t) / length);
+                moved = moved.max(step.magnitude());
+                positions.insert(*id, positions[id] + step);
+            }
+        }
+
+        stats.iterations = i + 1;
+        stats.max_displacement = moved;
+        if moved < config.tolerance {
+            stats.converged = true;
+            break;
+        }
+    }
+    LayoutResult { positions, stats }
+}
@@ -0,0 +1,126 @@
+use crate::algorithms::{layout_with, LayoutConfig, LayoutResult, LayoutStats};
+use crate::generators::seeded_rng;
+use crate::geometry::{Scalar, Vect};
+use crate::Graph;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Relayout after an edit that keeps the mental map. Nodes with a previous position start
+// there, nodes new to the graph start next to their placed neighbours, and only nodes
+// within `hops` of a new node may move; the rest are pinned along with config.pinned.
+// New nodes start this fraction of spring_length away from their neighbours' centre.
+const JITTER: Scalar = 0.1;
+
+// a position for every node not in `previous`, next to the centre of its placed
+// neighbours, or of the whole picture when none of them is placed.
+fn place_new_nodes<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    previous: &HashMap<ID, Vect>,
+    config: &LayoutConfig<ID>,
+) -> HashMap<ID, Vect> {
+    let mut rng = match config.seed {
+        Some(seed) => seeded_rng(seed),
+        None => StdRng::from_entropy(),
+    };
+    let jitter = config.spring_length * JITTER;
+    let mut placed: HashMap<ID, Vect> = g
+        .nodes
+        .keys()
+        .filter_map(|id| previous.get(id).map(|p| (*id, *p)))
+        .collect();
+    let centre = |points: &[Vect]| -> Option<Vect> {
+        if points.is_empty() {
+            return None;
+        }
+        let sum = points.iter().fold(Vect::new(0., 0., 0.), |acc, p| acc + *p);
+        Some(sum.scalar_mul(1.0 / points.len() as Scalar))
+    };
+
+    let mut waiting: Vec<ID> = g
+        .nodes
+        .keys()
+        .filter(|id| !placed.contains_key(id))
+        .copied()
+        .sorted()
+        .collect();
+    // rounds outwards from the placed nodes, so chains of new nodes follow each other.
+    while !waiting.is_empty() {
+        let mut round = Vec::new();
+        for id in waiting.iter() {
+            let anchors: Vec<Vect> = g
+                .neighbors(*id)
+                .unwrap()
+                .iter()
+                .sorted()
+                .filter_map(|n| placed.get(n).copied())
+                .collect();
+            if let Some(c) = centre(&anchors) {
+                round.push((*id, c));
+            }
+        }
+        if round.is_empty() {
+            // nothing left touches the picture, start the next one at its centre.
+            let all: Vec<Vect> = placed.values().copied().collect();
+            let c = centre(&all).unwrap_or_else(|| Vect::new(0., 0., 0.));
+            round.push((waiting[0], c));
+        }
+        for (id, c) in round {
+            let offset = Vect::random_from(&mut rng, -jitter, jitter, config.use_z);
+            placed.insert(id, c + offset);
+            waiting.retain(|w| *w != id);
+        }
+    }
+    placed
+}
+
+pub fn incremental_layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    previous: &HashMap<ID, Vect>,
+    hops: usize,
+    config: &LayoutConfig<ID>,
+) -> LayoutResult<ID> {
+    let start = place_new_nodes(g, previous, config);
+
+    // breadth first from every new node at once.
+    let mut free: HashMap<ID, usize> = HashMap::new();
+    let mut q: VecDeque<ID> = VecDeque::new();
+    for id in g.nodes.keys().filter(|id| !previous.contains_key(id)) {
+        free.insert(*id, 0);
+        q.push_back(*id);
+    }
+    while let Some(v) = q.pop_front() {
+        let d = free[&v];
+        if d == hops {
+            continue;
+        }
+        for w in g.neighbors(v).unwrap().into_iter().sorted() {
+            free.entry(w).or_insert_with(|| {
+                q.push_back(w);
+                d + 1
+            });
+        }
+    }
+    let pinned: HashSet<ID> = g
+        .nodes
+        .keys()
+        .filter(|id| !free.contains_key(id) || config.pinned.contains(id))
+        .copied()
+        .collect();
+
+    if pinned.len() == g.nodes.len() {
+        return LayoutResult {
+            positions: start,
+            stats: LayoutStats {
+                iterations: 0,
+                converged: true,
+                max_displacement: 0.0,
+            },
+        };
+    }
+    let config = config.clone().initial_positions(start).pinned(pinned);
+    layout_with(g, &config)
+}
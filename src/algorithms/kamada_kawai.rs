@@ -0,0 +1,174 @@
+use crate::algorithms::{create_initial_positions, LayoutConfig, LayoutResult, LayoutStats};
+use crate::geometry::{Scalar, Vect};
+use crate::Graph;
+use itertools::Itertools;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Kamada & Kawai (1989). Every pair is a spring of length spring_length * graph distance and
+// stiffness spring_const / distance². Each iteration moves the node with the largest energy
+// gradient by Newton-Raphson steps until its own gradient is below the tolerance; pinned
+// nodes are never chosen.
+// The tolerance bounds that gradient rather than the displacement. Pairs in different
+// components are treated as one hop further apart than the diameter.
+const NEWTON_STEPS: usize = 20;
+const DEFAULT_TOLERANCE: Scalar = 1e-4;
+
+// hop counts from every node, None where unreachable.
+pub(crate) fn hop_distances<T, E, ID: Copy + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    ids: &[ID],
+) -> Vec<Vec<Option<usize>>> {
+    let index: HashMap<ID, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut all = Vec::with_capacity(ids.len());
+    for source in 0..ids.len() {
+        let mut dist: Vec<Option<usize>> = vec![None; ids.len()];
+        let mut q: VecDeque<usize> = VecDeque::new();
+        dist[source] = Some(0);
+        q.push_back(source);
+        while let Some(u) = q.pop_front() {
+            let next = dist[u].unwrap() + 1;
+            for v in g.neighbors(ids[u]).unwrap() {
+                let v = index[&v];
+                if dist[v].is_none() {
+                    dist[v] = Some(next);
+                    q.push_back(v);
+                }
+            }
+        }
+        all.push(dist);
+    }
+    all
+}
+
+struct Springs {
+    length: Vec<Vec<Scalar>>,
+    strength: Vec<Vec<Scalar>>,
+}
+
+impl Springs {
+    // first and second derivatives of the energy with respect to point m.
+    fn derivatives(&self, points: &[Vect], m: usize) -> ([Scalar; 3], [[Scalar; 3]; 3]) {
+        let mut grad = [0.0; 3];
+        let mut hess = [[0.0; 3]; 3];
+        let p = points[m];
+        for (i, q) in points.iter().enumerate() {
+            let d = p.euclid_distance(q);
+            if i == m || d == 0.0 {
+                continue;
+            }
+            let delta = [p.x - q.x, p.y - q.y, p.z - q.z];
+            let (k, l) = (self.strength[m][i], self.length[m][i]);
+            for a in 0..3 {
+                grad[a] += k * (delta[a] - l * delta[a] / d);
+                for b in 0..3 {
+                    let identity = if a == b { 1.0 } else { 0.0 };
+                    hess[a][b] +=
+                        k * (identity - l * (identity * d * d - delta[a] * delta[b]) / d.powi(3));
+                }
+            }
+        }
+        (grad, hess)
+    }
+}
+
+fn norm(v: &[Scalar; 3]) -> Scalar {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+// solves h x = -g over the first `dims` coordinates, None when singular.
+fn newton_step(grad: [Scalar; 3], hess: [[Scalar; 3]; 3], dims: usize) -> Option<[Scalar; 3]> {
+    let mut a = hess;
+    let mut b = grad.map(|x| -x);
+    for col in 0..dims {
+        let pivot = (col..dims).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let top = a[col];
+        for row in col + 1..dims {
+            let f = a[row][col] / top[col];
+            for (c, v) in a[row].iter_mut().enumerate().take(dims).skip(col) {
+                *v -= f * top[c];
+            }
+            b[row] -= f * b[col];
+        }
+    }
+    let mut x = [0.0; 3];
+    for row in (0..dims).rev() {
+        let rest: Scalar = (row + 1..dims).map(|c| a[row][c] * x[c]).sum();
+        x[row] = (b[row] - rest) / a[row][row];
+    }
+    Some(x)
+}
+
+pub(crate) fn layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    config: &LayoutConfig<ID>,
+) -> LayoutResult<ID> {
+    let positions = create_initial_positions(g, config);
+    let mut stats = LayoutStats {
+        iterations: 0,
+        converged: false,
+        max_displacement: 0.0,
+    };
+    let ids: Vec<ID> = g.nodes.keys().copied().sorted().collect();
+    let mut points: Vec<Vect> = ids.iter().map(|id| positions[id]).collect();
+    let dims = if config.use_z { 3 } else { 2 };
+    let tolerance = if config.tolerance > 0.0 {
+        config.tolerance
+    } else {
+        DEFAULT_TOLERANCE
+    };
+
+    let hops = hop_distances(g, &ids);
+    let diameter = hops.iter().flatten().flatten().copied().max().unwrap_or(0);
+    let mut springs = Springs {
+        length: vec![vec![0.0; ids.len()]; ids.len()],
+        strength: vec![vec![0.0; ids.len()]; ids.len()],
+    };
+    for (i, row) in hops.iter().enumerate() {
+        for (j, h) in row.iter().enumerate() {
+            if i != j {
+                let h = h.unwrap_or(diameter + 1) as Scalar;
+                springs.length[i][j] = config.spring_length * h;
+                springs.strength[i][j] = config.spring_const / (h * h);
+            }
+        }
+    }
+
+    for i in 0..config.iterations {
+        let worst = (0..points.len())
+            .filter(|m| !config.pinned.contains(&ids[*m]))
+            .map(|m| (m, norm(&springs.derivatives(&points, m).0)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let m = match worst {
+            Some((m, gradient)) if gradient >= tolerance => m,
+            _ => {
+                stats.converged = true;
+                break;
+            }
+        };
+
+        let start = points[m];
+        for _ in 0..NEWTON_STEPS {
+            let (grad, hess) = springs.derivatives(&points, m);
+            if norm(&grad) < tolerance {
+                break;
+            }
+            match newton_step(grad, hess, dims) {
+                Some(x) => points[m] += Vect::new(x[0], x[1], x[2]),
+                None => break,
+            }
+        }
+        stats.iterations = i + 1;
+        stats.max_displacement = start.euclid_distance(&points[m]);
+    }
+    LayoutResult {
+        positions: ids.into_iter().zip(points).collect(),
+        stats,
+    }
+}
@@ -0,0 +1,740 @@
+use crate::algorithms::barneshut::BarnesHutTree;
+use crate::algorithms::circular::{circular_layout, circular_order, CircularOrdering};
+use crate::algorithms::crossing_reduction::reduce_crossings;
+use crate::algorithms::incremental::incremental_layout;
+use crate::algorithms::metrics::{
+    angular_resolution, edge_crossings, edge_length_variance, layout_quality,
+    neighbourhood_preservation, node_overlaps, stress,
+};
+use crate::algorithms::packing::{component_layout, connected_components, pack_rectangles};
+use crate::algorithms::spectral::{
+    algebraic_connectivity, fiedler_vector, laplacian_matrix, laplacian_spectrum,
+    normalized_laplacian_matrix, spectral_layout,
+};
+use crate::algorithms::sugiyama::{layered_layout, LayeredConfig, Ordering, Ranking};
+use crate::algorithms::tree::{radial_tree_layout, tidy_tree_layout};
+use crate::algorithms::{
+    connections, layout_with, routed_connections, Cooling, LayoutAlgorithm, LayoutConfig,
+};
+use crate::generators::seeded_rng;
+use crate::generators::structured::{
+    binary_tree, complete_graph, cycle_graph, grid_2d_graph, path_graph, petersen_graph,
+};
+use crate::geometry::{Scalar, Vect};
+use crate::graph::Graph;
+use crate::utils::matrix::IdOrder;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+
+type Fixture = Graph<(), (), u32>;
+
+#[test]
+fn seeded_layout_repeats() {
+    let (g, _): (Fixture, _) = cycle_graph(12);
+    let config = LayoutConfig::new().seed(3).iterations(50);
+    let a = layout_with(&g, &config);
+    let b = layout_with(&g, &config);
+    assert_eq!(a.positions, b.positions);
+    assert_eq!(50, a.stats.iterations);
+    assert!(!a.stats.converged);
+}
+
+#[test]
+fn layout_stops_when_converged() {
+    let (g, _): (Fixture, _) = path_graph(4);
+    let config = LayoutConfig::new()
+        .seed(1)
+        .iterations(5000)
+        .initial_range(0., 10.)
+        .cooling(Cooling::Exponential(0.99))
+        .tolerance(1e-3);
+    let result = layout_with(&g, &config);
+    assert!(result.stats.converged);
+    assert!(result.stats.iterations < 5000);
+    assert!(result.stats.max_displacement < 1e-3);
+}
+
+#[test]
+fn layout_uses_initial_positions_and_z() {
+    let (g, start): (Fixture, HashMap<u32, Vect>) = cycle_graph(6);
+    let config = LayoutConfig::new()
+        .initial_positions(start.clone())
+        .iterations(0);
+    assert_eq!(start, layout_with(&g, &config).positions);
+
+    let config = LayoutConfig::new().seed(2).iterations(10).three_d(true);
+    let result = layout_with(&g, &config);
+    assert!(result.positions.values().any(|p| p.z != 0.0));
+}
+
+#[test]
+fn coincident_nodes_stay_finite() {
+    let (g, _): (Fixture, _) = cycle_graph(5);
+    let start: HashMap<u32, Vect> = (0..5).map(|id| (id, Vect::new(1., 1., 0.))).collect();
+    for theta in [0.0, 0.5] {
+        let config = LayoutConfig::new()
+            .iterations(10)
+            .theta(theta)
+            .initial_positions(start.clone());
+        let result = layout_with(&g, &config);
+        assert!(result
+            .positions
+            .values()
+            .all(|p| p.x.is_finite() && p.y.is_finite() && p.z.is_finite()));
+    }
+}
+
+#[test]
+fn pinned_nodes_stay_put() {
+    let (g, start): (Fixture, HashMap<u32, Vect>) = cycle_graph(8);
+    let pinned: HashSet<u32> = HashSet::from([0, 3]);
+    for algorithm in [
+        LayoutAlgorithm::Eades,
+        LayoutAlgorithm::FruchtermanReingold { temperature: 1.0 },
+        LayoutAlgorithm::KamadaKawai,
+    ] {
+        let config = LayoutConfig::new()
+            .iterations(30)
+            .initial_positions(start.clone())
+            .pinned(pinned.clone())
+            .algorithm(algorithm);
+        let result = layout_with(&g, &config);
+        for id in pinned.iter() {
+            assert_eq!(start[id], result.positions[id]);
+        }
+        assert!(result.positions.iter().any(|(id, p)| *p != start[id]));
+    }
+}
+
+#[test]
+fn incremental_layout_only_relaxes_near_new_nodes() {
+    let (mut g, _): (Fixture, _) = path_graph(8);
+    let config = LayoutConfig::new()
+        .seed(5)
+        .iterations(200)
+        .initial_range(0., 10.)
+        .algorithm(LayoutAlgorithm::KamadaKawai);
+    let before = layout_with(&g, &config).positions;
+    g.add_node(8, ());
+    g.add_node(9, ());
+    g.add_edge(1000, 7, 8, ()).unwrap();
+    g.add_edge(1001, 8, 9, ()).unwrap();
+
+    let after = incremental_layout(&g, &before, 1, &config);
+    for id in 0..7 {
+        assert_eq!(before[&id], after.positions[&id]);
+    }
+    for id in [8, 9] {
+        let p = after.positions[&id];
+        assert!(p.x.is_finite() && p.y.is_finite());
+        assert!(p.euclid_distance(&after.positions[&7]) < 3.0 * config.spring_length);
+    }
+
+    // without new nodes nothing moves.
+    let again = incremental_layout(&g, &after.positions, 2, &config);
+    assert_eq!(after.positions, again.positions);
+    assert_eq!(0, again.stats.iterations);
+}
+
+fn inverse_square(p: &Vect, q: &Vect) -> Vect {
+    let d = *p - *q;
+    d.scalar_mul(1.0 / d.magnitude().powi(3))
+}
+
+#[test]
+fn barnes_hut_matches_exact_sum() {
+    let mut rng = seeded_rng(5);
+    let points: Vec<Vect> = (0..400)
+        .map(|_| Vect::random_from(&mut rng, 0., 100., true))
+        .collect();
+    let tree = BarnesHutTree::new(&points);
+    for ix in (0..points.len()).step_by(37) {
+        let exact = points
+            .iter()
+            .enumerate()
+            .filter(|(jx, _)| *jx != ix)
+            .fold(Vect::new(0., 0., 0.), |acc, (_, q)| {
+                acc + inverse_square(&points[ix], q)
+            });
+        let zero = tree.force_on(ix, 0.0, inverse_square);
+        assert!((zero - exact).magnitude() < 1e-9 * exact.magnitude().max(1.0));
+        let approx = tree.force_on(ix, 0.5, inverse_square);
+        assert!((approx - exact).magnitude() < 0.05 * exact.magnitude());
+    }
+}
+
+#[test]
+fn barnes_hut_handles_coincident_points() {
+    let points = vec![Vect::new(1., 1., 0.); 5];
+    let tree = BarnesHutTree::new(&points);
+    let f = tree.force_on(0, 0.5, |_, _| Vect::new(1., 0., 0.));
+    assert_eq!(Vect::new(4., 0., 0.), f);
+}
+
+#[test]
+fn exact_and_barnes_hut_layouts_agree() {
+    let (g, _): (Fixture, _) = cycle_graph(30);
+    let exact = LayoutConfig::new()
+        .seed(4)
+        .iterations(1)
+        .initial_range(0., 30.)
+        .theta(0.0);
+    let approx = exact.clone().theta(0.3);
+    let start = layout_with(&g, &exact.clone().iterations(0)).positions;
+    let a = layout_with(&g, &exact).positions;
+    let b = layout_with(&g, &approx).positions;
+    // the approximation error is small next to the step itself.
+    let step: Scalar = a
+        .keys()
+        .map(|k| a[k].euclid_distance(&start[k]))
+        .fold(0.0, Scalar::max);
+    let worst: Scalar = a
+        .keys()
+        .map(|k| a[k].euclid_distance(&b[k]))
+        .fold(0.0, Scalar::max);
+    assert!(step > 0.0);
+    assert!(worst < 0.05 * step);
+}
+
+#[test]
+fn barnes_hut_lays_out_large_graph() {
+    let (g, _): (Fixture, _) = path_graph(20000);
+    let config = LayoutConfig::new().seed(6).iterations(3);
+    let result = layout_with(&g, &config);
+    assert_eq!(20000, result.positions.len());
+    assert!(result.positions.values().all(|p| p.x.is_finite()));
+}
+
+#[test]
+fn components_largest_first() {
+    let mut g = directed(&[(5, 6), (1, 2), (2, 3)]);
+    g.add_node(4, ());
+    g.add_node(0, ());
+    assert_eq!(
+        vec![vec![1, 2, 3], vec![5, 6], vec![0], vec![4]],
+        connected_components(&g)
+    );
+}
+
+fn overlap(a: (Scalar, Scalar, Scalar, Scalar), b: (Scalar, Scalar, Scalar, Scalar)) -> bool {
+    a.0 < b.2 && b.0 < a.2 && a.1 < b.3 && b.1 < a.3
+}
+
+#[test]
+fn packed_rectangles_keep_apart() {
+    let sizes = [
+        (4., 1.),
+        (1., 1.),
+        (2., 3.),
+        (0., 0.),
+        (5., 2.),
+        (1., 4.),
+        (3., 3.),
+    ];
+    let spacing = 0.5;
+    let corners = pack_rectangles(&sizes, spacing);
+    // each box grown by the spacing must not reach into another.
+    let boxes: Vec<_> = sizes
+        .iter()
+        .zip(corners.iter())
+        .map(|((w, h), (x, y))| (*x, *y, x + w + spacing, y + h + spacing))
+        .collect();
+    for (a, b) in boxes.iter().tuple_combinations() {
+        assert!(!overlap(*a, *b));
+    }
+    let width = boxes.iter().map(|b| b.2).fold(0.0, Scalar::max);
+    let height = boxes.iter().map(|b| b.3).fold(0.0, Scalar::max);
+    let area: Scalar = sizes
+        .iter()
+        .map(|(w, h)| (w + spacing) * (h + spacing))
+        .sum();
+    assert!(width * height < 2.5 * area);
+}
+
+#[test]
+fn component_layout_packs_pieces() {
+    let mut g = directed(&[(1, 2), (2, 3), (3, 1), (4, 5), (6, 7), (7, 8)]);
+    for id in 10..16 {
+        g.add_node(id, ());
+    }
+    let config = LayoutConfig::new()
+        .seed(6)
+        .iterations(100)
+        .algorithm(LayoutAlgorithm::KamadaKawai);
+    let spacing = 1.0;
+    let positions = component_layout(&g, &config, spacing);
+    assert_eq!(g.nodes.len(), positions.len());
+
+    let boxes: Vec<_> = connected_components(&g)
+        .iter()
+        .map(|c| {
+            let xs = c.iter().map(|id| positions[id].x).collect_vec();
+            let ys = c.iter().map(|id| positions[id].y).collect_vec();
+            let lo = |v: &[Scalar]| v.iter().copied().fold(Scalar::MAX, Scalar::min);
+            let hi = |v: &[Scalar]| v.iter().copied().fold(Scalar::MIN, Scalar::max);
+            (lo(&xs), lo(&ys), hi(&xs) + spacing, hi(&ys) + spacing)
+        })
+        .collect();
+    for (a, b) in boxes.iter().tuple_combinations() {
+        assert!(!overlap(*a, *b));
+    }
+    // nothing is flung away: the picture stays within a few spring lengths.
+    for p in positions.values() {
+        assert!(p.x >= 0.0 && p.y >= 0.0);
+        assert!(p.x < 10.0 * config.spring_length && p.y < 10.0 * config.spring_length);
+    }
+}
+
+fn at(points: &[(u32, Scalar, Scalar)]) -> HashMap<u32, Vect> {
+    points
+        .iter()
+        .map(|(id, x, y)| (*id, Vect::new(*x, *y, 0.)))
+        .collect()
+}
+
+#[test]
+fn crossings_and_angles() {
+    let (k4, _): (Fixture, _) = complete_graph(4);
+    let square = at(&[(0, 0., 0.), (1, 1., 0.), (2, 1., 1.), (3, 0., 1.)]);
+    assert_eq!(1, edge_crossings(&k4, &square));
+    assert!((angular_resolution(&k4, &square) - PI / 4.0).abs() < 1e-9);
+
+    let (k5, _): (Fixture, _) = complete_graph(5);
+    let pentagon = circular_layout(&k5, 1.0, CircularOrdering::ById);
+    assert_eq!(5, edge_crossings(&k5, &pentagon));
+
+    let star = directed(&[(0, 1), (0, 2), (0, 3), (0, 4)]);
+    let cross = at(&[
+        (0, 0., 0.),
+        (1, 1., 0.),
+        (2, 0., 1.),
+        (3, -1., 0.),
+        (4, 0., -1.),
+    ]);
+    assert_eq!(0, edge_crossings(&star, &cross));
+    assert!((angular_resolution(&star, &cross) - PI / 2.0).abs() < 1e-9);
+    assert!(edge_length_variance(&star, &cross).abs() < 1e-12);
+    // lengths 1, 1, 3 and 3: mean 2 and variance 1.
+    let uneven = at(&[
+        (0, 0., 0.),
+        (1, 1., 0.),
+        (2, 0., 1.),
+        (3, -3., 0.),
+        (4, 0., -3.),
+    ]);
+    assert!((edge_length_variance(&star, &uneven) - 0.25).abs() < 1e-12);
+    let doubled: HashMap<u32, Vect> = uneven
+        .iter()
+        .map(|(id, p)| (*id, p.scalar_mul(2.0)))
+        .collect();
+    assert!((edge_length_variance(&star, &doubled) - 0.25).abs() < 1e-12);
+}
+
+#[test]
+fn stress_and_neighbourhoods() {
+    let (g, line): (Fixture, HashMap<u32, Vect>) = path_graph(5);
+    assert!(stress(&g, &line).abs() < 1e-12);
+    let stretched: HashMap<u32, Vect> = line
+        .iter()
+        .map(|(id, p)| (*id, p.scalar_mul(3.0)))
+        .collect();
+    assert!(stress(&g, &stretched).abs() < 1e-12);
+    assert!((neighbourhood_preservation(&g, &line) - 1.0).abs() < 1e-12);
+
+    // folding the path back on itself puts strangers next to each other.
+    let folded = at(&[
+        (0, 0., 0.),
+        (1, 1., 0.),
+        (2, 2., 0.),
+        (3, 1., 0.2),
+        (4, 0., 0.2),
+    ]);
+    assert!(stress(&g, &folded) > 0.1);
+    assert!(neighbourhood_preservation(&g, &folded) < 1.0);
+    assert_eq!(2, node_overlaps(&folded, 0.2));
+
+    let quality = layout_quality(&g, &line, 0.2);
+    assert_eq!(0, quality.crossings);
+    assert_eq!(0, quality.overlaps);
+    assert!((quality.angular_resolution - PI).abs() < 1e-9);
+}
+
+#[test]
+fn swaps_remove_crossings() {
+    let (g, _): (Fixture, _) = cycle_graph(4);
+    let bowtie = at(&[(0, 0., 0.), (1, 1., 1.), (2, 1., 0.), (3, 0., 1.)]);
+    assert_eq!(1, edge_crossings(&g, &bowtie));
+    let fixed = reduce_crossings(&g, &bowtie, 5);
+    assert_eq!(0, edge_crossings(&g, &fixed));
+    // the same places are used, only by different nodes.
+    let places = |p: &HashMap<u32, Vect>| {
+        p.values()
+            .map(|v| ((v.x * 10.) as i64, (v.y * 10.) as i64))
+            .sorted()
+            .collect_vec()
+    };
+    assert_eq!(places(&bowtie), places(&fixed));
+
+    let (g, _): (Fixture, _) = petersen_graph();
+    let start = circular_layout(&g, 1.0, CircularOrdering::ById);
+    let fixed = reduce_crossings(&g, &start, 10);
+    assert!(edge_crossings(&g, &fixed) < edge_crossings(&g, &start));
+}
+
+fn edge_lengths(g: &Fixture, positions: &HashMap<u32, Vect>) -> Vec<Scalar> {
+    g.edges
+        .values()
+        .map(|e| positions[&e.left].euclid_distance(&positions[&e.right]))
+        .collect()
+}
+
+#[test]
+fn eades_springs_do_not_repel_neighbours() {
+    let (g, _): (Fixture, _) = path_graph(2);
+    let start: HashMap<u32, Vect> = [(0, Vect::new(0., 0., 0.)), (1, Vect::new(2., 0., 0.))]
+        .into_iter()
+        .collect();
+    let config = LayoutConfig::new()
+        .initial_positions(start.clone())
+        .iterations(1);
+    assert_eq!(start, layout_with(&g, &config).positions);
+}
+
+#[test]
+fn fruchterman_reingold_evens_out_edges() {
+    let (g, _): (Fixture, _) = cycle_graph(10);
+    let config = LayoutConfig::new()
+        .seed(8)
+        .initial_range(0., 10.)
+        .spring_length(1.0)
+        .iterations(300)
+        .algorithm(LayoutAlgorithm::FruchtermanReingold { temperature: 1.0 });
+    let result = layout_with(&g, &config);
+    assert_eq!(300, result.stats.iterations);
+    let lengths = edge_lengths(&g, &result.positions);
+    let (min, max) = lengths
+        .iter()
+        .fold((Scalar::MAX, 0.0), |(lo, hi): (Scalar, Scalar), l| {
+            (lo.min(*l), hi.max(*l))
+        });
+    assert!(max / min < 1.2, "{:?}", lengths);
+}
+
+#[test]
+fn kamada_kawai_matches_graph_distances() {
+    let (g, _): (Fixture, _) = path_graph(5);
+    let config = LayoutConfig::new()
+        .seed(9)
+        .initial_range(0., 5.)
+        .iterations(1000)
+        .spring_length(1.0)
+        .algorithm(LayoutAlgorithm::KamadaKawai);
+    let result = layout_with(&g, &config);
+    assert!(result.stats.converged);
+    for l in edge_lengths(&g, &result.positions) {
+        assert!((l - 1.0).abs() < 0.01, "{}", l);
+    }
+    let ends = result.positions[&0].euclid_distance(&result.positions[&4]);
+    assert!((ends - 4.0).abs() < 0.01);
+
+    let (g, _): (Fixture, _) = grid_2d_graph(3, 3, false);
+    let config = config.three_d(true);
+    let result = layout_with(&g, &config);
+    assert!(result.stats.converged);
+    assert_eq!(9, result.positions.len());
+}
+
+fn directed(edges: &[(u32, u32)]) -> Fixture {
+    let mut g: Fixture = Graph::new();
+    for (l, r) in edges {
+        g.add_node(*l, ());
+        g.add_node(*r, ());
+    }
+    for (ix, (l, r)) in edges.iter().enumerate() {
+        g.add_edge(100 + ix as u32, *l, *r, ()).unwrap();
+    }
+    g
+}
+
+#[test]
+fn layered_edges_point_down() {
+    let g = directed(&[(1, 2), (1, 3), (2, 4), (3, 4), (4, 5), (1, 5)]);
+    for ranking in [Ranking::LongestPath, Ranking::NetworkSimplex] {
+        let result = layered_layout(&g, &LayeredConfig::new().ranking(ranking));
+        assert!(result.reversed.is_empty());
+        for e in g.edges.values() {
+            assert!(result.layers[&e.left] < result.layers[&e.right]);
+            assert!(result.positions[&e.left].y < result.positions[&e.right].y);
+        }
+        // 1 -> 5 crosses layers 1 and 2.
+        let bends = &result.bends[&105];
+        assert_eq!(2, bends.len());
+        assert_eq!(
+            vec![1.0, 2.0],
+            bends.iter().map(|b| b.y).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            8,
+            routed_connections(&result.positions, &result.bends, &g).len()
+        );
+    }
+}
+
+#[test]
+fn layered_breaks_cycles() {
+    let g = directed(&[(1, 2), (2, 3), (3, 1)]);
+    let result = layered_layout(&g, &LayeredConfig::new());
+    assert_eq!(1, result.reversed.len());
+    let layers: Vec<usize> = (1..4).map(|v| result.layers[&v]).sorted().collect();
+    assert_eq!(vec![0, 1, 2], layers);
+    let e = &g.edges[result.reversed.iter().next().unwrap()];
+    assert!(result.layers[&e.left] > result.layers[&e.right]);
+    // the reversed edge's bend still runs from its left node to its right node.
+    for (id, bends) in result.bends.iter() {
+        let e = &g.edges[id];
+        for b in bends {
+            let (top, bottom) = (result.positions[&e.left].y, result.positions[&e.right].y);
+            assert!(b.y > top.min(bottom) && b.y < top.max(bottom));
+        }
+    }
+}
+
+#[test]
+fn network_simplex_shortens_edges() {
+    // the source 5 only feeds the sink, longest path puts it on the top layer.
+    let g = directed(&[(1, 2), (2, 3), (3, 4), (5, 4)]);
+    let span = |ranking| {
+        let result = layered_layout(&g, &LayeredConfig::new().ranking(ranking));
+        g.edges
+            .values()
+            .map(|e| result.layers[&e.right] - result.layers[&e.left])
+            .sum::<usize>()
+    };
+    assert_eq!(6, span(Ranking::LongestPath));
+    assert_eq!(4, span(Ranking::NetworkSimplex));
+}
+
+#[test]
+fn layered_removes_avoidable_crossings() {
+    let g = directed(&[(1, 4), (2, 3), (1, 6), (2, 5), (3, 7), (6, 8)]);
+    for ordering in [Ordering::Barycenter, Ordering::Median] {
+        let config = LayeredConfig::new().ordering(ordering).node_spacing(2.0);
+        let result = layered_layout(&g, &config);
+        let x = |v: u32| result.positions[&v].x;
+        let segments = routed_connections(&result.positions, &result.bends, &g);
+        for (a, b) in segments.iter().tuple_combinations() {
+            if a.0.y == b.0.y && a.0 != b.0 && a.1 != b.1 {
+                assert_eq!(a.0.x < b.0.x, a.1.x < b.1.x, "{:?} {:?}", a, b);
+            }
+        }
+        let layer = |l: usize| {
+            (1..9)
+                .filter(|v| result.layers[v] == l)
+                .map(x)
+                .sorted_by(|a, b| a.total_cmp(b))
+                .collect::<Vec<_>>()
+        };
+        for l in 0..3 {
+            for (a, b) in layer(l).iter().tuple_windows() {
+                assert!(b - a >= 2.0 - 1e-9);
+            }
+        }
+    }
+}
+
+#[test]
+fn tidy_tree_is_layered_and_centred() {
+    let (g, _): (Fixture, _) = binary_tree(3);
+    let positions = tidy_tree_layout(&g, 0, 1.0, 2.0).unwrap();
+    assert_eq!(Vect::new(0., 0., 0.), positions[&0]);
+    assert_eq!(g.nodes.len(), positions.len());
+    // parents sit midway over their outer children, one level above them.
+    for (v, p) in positions.iter() {
+        let kids: Vec<&Vect> = g
+            .neighbors(*v)
+            .unwrap()
+            .iter()
+            .map(|c| &positions[c])
+            .filter(|c| c.y > p.y)
+            .collect();
+        if !kids.is_empty() {
+            assert!(kids.iter().all(|c| c.y == p.y + 2.0));
+            let (lo, hi) = kids.iter().fold((Scalar::MAX, Scalar::MIN), |(lo, hi), c| {
+                (lo.min(c.x), hi.max(c.x))
+            });
+            assert!((p.x - (lo + hi) / 2.0).abs() < 1e-9);
+        }
+    }
+    let leaves: Vec<Scalar> = positions
+        .values()
+        .filter(|p| p.y == 6.0)
+        .map(|p| p.x)
+        .sorted_by(|a, b| a.total_cmp(b))
+        .collect();
+    assert_eq!(8, leaves.len());
+    for (a, b) in leaves.iter().tuple_windows() {
+        assert!((b - a - 1.0).abs() < 1e-9);
+    }
+    assert!(tidy_tree_layout(&g, 99, 1.0, 1.0).is_err());
+}
+
+#[test]
+fn tidy_tree_places_every_component() {
+    let mut g = directed(&[(1, 2), (1, 3), (4, 5)]);
+    g.add_node(6, ());
+    let positions = tidy_tree_layout(&g, 4, 1.0, 1.0).unwrap();
+    assert_eq!(6, positions.len());
+    assert_eq!(Vect::new(0., 0., 0.), positions[&4]);
+    assert!(positions[&2].x > positions[&5].x);
+    assert!(positions[&6].x > positions[&3].x);
+    assert_eq!(3, connections(&positions, &g).len() / 2);
+}
+
+#[test]
+fn radial_tree_rings() {
+    let (g, _): (Fixture, _) = binary_tree(3);
+    let positions = radial_tree_layout(&g, 0, 1.5).unwrap();
+    assert_eq!(Vect::new(0., 0., 0.), positions[&0]);
+    let rings: Vec<usize> = positions
+        .values()
+        .map(|p| (p.magnitude() / 1.5).round() as usize)
+        .sorted()
+        .collect();
+    assert_eq!(vec![0, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3], rings);
+    for p in positions.values() {
+        assert!((p.magnitude() / 1.5 - (p.magnitude() / 1.5).round()).abs() < 1e-9);
+    }
+}
+
+fn circle_crossings(g: &Fixture, order: &[u32]) -> usize {
+    let pos: HashMap<u32, usize> = order.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+    let chords: Vec<(usize, usize)> = g
+        .edges
+        .values()
+        .map(|e| {
+            let (a, b) = (pos[&e.left], pos[&e.right]);
+            (a.min(b), a.max(b))
+        })
+        .collect();
+    chords
+        .iter()
+        .tuple_combinations()
+        .filter(|((a, b), (c, d))| {
+            a != c && a != d && b != c && b != d && ((a < c && c < b) != (a < d && d < b))
+        })
+        .count()
+}
+
+#[test]
+fn circular_orderings_reduce_crossings() {
+    // a cycle given scrambled ids.
+    let mut g: Fixture = Graph::new();
+    let ring = [0, 5, 2, 7, 4, 1, 6, 3];
+    for v in ring {
+        g.add_node(v, ());
+    }
+    for (ix, (a, b)) in ring.iter().circular_tuple_windows().enumerate() {
+        g.add_edge(10 + ix as u32, *a, *b, ()).unwrap();
+    }
+    let by_id = circle_crossings(&g, &circular_order(&g, CircularOrdering::ById));
+    let connected = circle_crossings(&g, &circular_order(&g, CircularOrdering::Connectivity));
+    let sifted = circle_crossings(&g, &circular_order(&g, CircularOrdering::Sifting));
+    assert!(by_id > 0);
+    assert_eq!(0, connected);
+    assert_eq!(0, sifted);
+
+    let (p, _): (Fixture, _) = petersen_graph();
+    let plain = circle_crossings(&p, &circular_order(&p, CircularOrdering::Connectivity));
+    let sifted = circle_crossings(&p, &circular_order(&p, CircularOrdering::Sifting));
+    assert!(sifted <= plain);
+
+    let (k, _): (Fixture, _) = complete_graph(6);
+    let positions = circular_layout(&k, 2.0, CircularOrdering::Sifting);
+    assert_eq!(6, positions.len());
+    assert!(positions
+        .values()
+        .all(|p| (p.magnitude() - 2.0).abs() < 1e-9));
+    assert_eq!(
+        Vect::new(2., 0., 0.),
+        positions[&circular_order(&k, CircularOrdering::Sifting)[0]]
+    );
+}
+
+#[test]
+fn laplacian_matrices() {
+    let (g, _): (Fixture, _) = path_graph(3);
+    let order = IdOrder::from_graph(&g);
+    let l = laplacian_matrix(&g, &order, |_| 1.0).to_dense();
+    assert_eq!(
+        vec![vec![1., -1., 0.], vec![-1., 2., -1.], vec![0., -1., 1.]],
+        l
+    );
+    let n = normalized_laplacian_matrix(&g, &order, |_| 1.0);
+    assert!((n.get(0, 1) + (0.5 as Scalar).sqrt()).abs() < 1e-12);
+    assert_eq!(1.0, n.get(1, 1));
+}
+
+#[test]
+fn laplacian_spectra_of_known_graphs() {
+    let (path, _): (Fixture, _) = path_graph(7);
+    let spectrum = laplacian_spectrum(&path);
+    for (k, value) in spectrum.iter().enumerate() {
+        let expected = 2.0 - 2.0 * (k as Scalar * PI / 7.0).cos();
+        assert!((value - expected).abs() < 1e-8, "{:?}", spectrum);
+    }
+    let (k6, _): (Fixture, _) = complete_graph(6);
+    let spectrum = laplacian_spectrum(&k6);
+    assert!(spectrum[0].abs() < 1e-8);
+    assert!(spectrum[1..].iter().all(|v| (v - 6.0).abs() < 1e-8));
+    assert!((algebraic_connectivity(&k6) - 6.0).abs() < 1e-8);
+
+    let split = directed(&[(1, 2), (3, 4)]);
+    assert!(algebraic_connectivity(&split).abs() < 1e-8);
+}
+
+#[test]
+fn fiedler_vector_bisects_a_path() {
+    let (g, _): (Fixture, _) = path_graph(10);
+    let f = fiedler_vector(&g);
+    let values: Vec<Scalar> = (0..10).map(|v| f[&v]).collect();
+    assert!(
+        values.iter().tuple_windows().all(|(a, b)| a > b)
+            || values.iter().tuple_windows().all(|(a, b)| a < b)
+    );
+    assert!(values[..5].iter().all(|v| v.signum() == values[0].signum()));
+    assert!(values[5..]
+        .iter()
+        .all(|v| v.signum() == -values[0].signum()));
+
+    let (grid, _): (Fixture, _) = grid_2d_graph(30, 20, false);
+    let expected = 2.0 - 2.0 * (PI / 30.0).cos();
+    assert!((algebraic_connectivity(&grid) - expected).abs() < 1e-6);
+}
+
+#[test]
+fn fiedler_vector_of_a_long_path_converges() {
+    let (g, _): (Fixture, _) = path_graph(300);
+    let expected = 2.0 - 2.0 * (PI / 300.0).cos();
+    assert!((algebraic_connectivity(&g) - expected).abs() < 1e-9);
+    let f = fiedler_vector(&g);
+    let values: Vec<Scalar> = (0..300).map(|v| f[&v]).collect();
+    assert!(
+        values.iter().tuple_windows().all(|(a, b)| a > b)
+            || values.iter().tuple_windows().all(|(a, b)| a < b)
+    );
+}
+
+#[test]
+fn spectral_layout_of_a_cycle_is_round() {
+    let (g, _): (Fixture, _) = cycle_graph(12);
+    let positions = spectral_layout(&g, 3.0, false);
+    let radii: Vec<Scalar> = positions.values().map(|p| p.magnitude()).collect();
+    assert!(radii.iter().all(|r| (r - radii[0]).abs() < 1e-6));
+    assert!(positions.values().all(|p| p.z == 0.0));
+    assert_eq!(positions, spectral_layout(&g, 3.0, false));
+    let top = positions
+        .values()
+        .fold(0.0, |m: Scalar, p| m.max(p.x.abs()));
+    assert!((top - 3.0).abs() < 1e-9);
+}
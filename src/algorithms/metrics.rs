@@ -0,0 +1,192 @@
+use crate::algorithms::kamada_kawai::hop_distances;
+use crate::geometry::{Scalar, Segment, Vect};
+use crate::Graph;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::TAU;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Quality measures of a drawing, for comparing layouts and their parameters. Edges are
+// straight lines between the node positions and self loops are left out.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutQuality {
+    pub crossings: usize,
+    pub stress: Scalar,
+    pub neighbourhood_preservation: Scalar,
+    pub edge_length_variance: Scalar,
+    pub angular_resolution: Scalar,
+    pub overlaps: usize,
+}
+
+pub fn layout_quality<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    positions: &HashMap<ID, Vect>,
+    node_radius: Scalar,
+) -> LayoutQuality {
+    LayoutQuality {
+        crossings: edge_crossings(g, positions),
+        stress: stress(g, positions),
+        neighbourhood_preservation: neighbourhood_preservation(g, positions),
+        edge_length_variance: edge_length_variance(g, positions),
+        angular_resolution: angular_resolution(g, positions),
+        overlaps: node_overlaps(positions, node_radius),
+    }
+}
+
+// end points of every edge other than self loops, in edge id order.
+pub(crate) fn segments<T, E, ID: Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+) -> Vec<(ID, ID)> {
+    g.edges
+        .keys()
+        .sorted()
+        .map(|e| (g.edges[e].left, g.edges[e].right))
+        .filter(|(l, r)| l != r)
+        .collect()
+}
+
+// whether two edges cross properly in x and y, never when they share a node.
+pub(crate) fn edges_cross<ID: Copy + Eq + Hash>(
+    positions: &HashMap<ID, Vect>,
+    (a, b): (ID, ID),
+    (c, d): (ID, ID),
+) -> bool {
+    if a == c || a == d || b == c || b == d {
+        return false;
+    }
+    Segment::new(positions[&a], positions[&b]).crosses(&Segment::new(positions[&c], positions[&d]))
+}
+
+pub fn edge_crossings<T, E, ID: Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    positions: &HashMap<ID, Vect>,
+) -> usize {
+    segments(g)
+        .into_iter()
+        .tuple_combinations()
+        .filter(|(e, f)| edges_cross(positions, *e, *f))
+        .count()
+}
+
+// Kamada–Kawai stress over connected pairs, sum of ((s |pi - pj| - dij) / dij)² with dij
+// the hop count and s the scale that fits the drawing best, so it does not depend on the
+// size of the drawing. Divided by the number of pairs.
+pub fn stress<T, E, ID: Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    positions: &HashMap<ID, Vect>,
+) -> Scalar {
+    let ids: Vec<ID> = g.nodes.keys().copied().sorted().collect();
+    let hops = hop_distances(g, &ids);
+    let pairs: Vec<(Scalar, Scalar)> = (0..ids.len())
+        .tuple_combinations()
+        .filter_map(|(i, j)| {
+            hops[i][j].map(|d| {
+                let x = positions[&ids[i]].euclid_distance(&positions[&ids[j]]);
+                (x, d as Scalar)
+            })
+        })
+        .collect();
+    if pairs.is_empty() {
+        return 0.0;
+    }
+    let fit: Scalar = pairs.iter().map(|(x, d)| x / d).sum();
+    let spread: Scalar = pairs.iter().map(|(x, d)| x * x / (d * d)).sum();
+    let s = if spread > 0.0 { fit / spread } else { 0.0 };
+    let total: Scalar = pairs.iter().map(|(x, d)| ((s * x - d) / d).powi(2)).sum();
+    total / pairs.len() as Scalar
+}
+
+// Mean over nodes with neighbours of the Jaccard index between the graph neighbours and as
+// many nearest nodes in the drawing; 1 when every node is drawn closest to its neighbours.
+pub fn neighbourhood_preservation<T, E, ID: Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    positions: &HashMap<ID, Vect>,
+) -> Scalar {
+    let ids: Vec<ID> = g.nodes.keys().copied().sorted().collect();
+    let mut total = 0.0;
+    let mut counted = 0;
+    for id in ids.iter() {
+        let neighbours: HashSet<ID> = g.neighbors(*id).unwrap();
+        if neighbours.is_empty() {
+            continue;
+        }
+        let p = positions[id];
+        let nearest: HashSet<ID> = ids
+            .iter()
+            .filter(|other| *other != id)
+            .sorted_by(|a, b| {
+                p.euclid_distance(&positions[a])
+                    .total_cmp(&p.euclid_distance(&positions[b]))
+            })
+            .take(neighbours.len())
+            .copied()
+            .collect();
+        let shared = nearest.intersection(&neighbours).count();
+        total += shared as Scalar / (2 * neighbours.len() - shared) as Scalar;
+        counted += 1;
+    }
+    if counted == 0 {
+        return 1.0;
+    }
+    total / counted as Scalar
+}
+
+// Variance of the edge lengths over their squared mean (the squared coefficient of
+// variation), so it does not change with the scale of the drawing. 0 when all edges are
+// equally long.
+pub fn edge_length_variance<T, E, ID: Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    positions: &HashMap<ID, Vect>,
+) -> Scalar {
+    let lengths: Vec<Scalar> = segments(g)
+        .iter()
+        .map(|(l, r)| positions[l].euclid_distance(&positions[r]))
+        .collect();
+    let mean = lengths.iter().sum::<Scalar>() / lengths.len().max(1) as Scalar;
+    if mean == 0.0 {
+        return 0.0;
+    }
+    lengths
+        .iter()
+        .map(|l| (l / mean - 1.0).powi(2))
+        .sum::<Scalar>()
+        / lengths.len() as Scalar
+}
+
+// smallest angle in radians between two edges leaving the same node, TAU without any.
+pub fn angular_resolution<T, E, ID: Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    positions: &HashMap<ID, Vect>,
+) -> Scalar {
+    let mut smallest = TAU;
+    for id in g.nodes.keys() {
+        let p = positions[id];
+        let directions: Vec<Vect> = g
+            .neighbors(*id)
+            .unwrap()
+            .iter()
+            .map(|n| positions[n] - p)
+            .filter(|d| d.magnitude() > 0.0)
+            .collect();
+        for (a, b) in directions.iter().tuple_combinations() {
+            let cos = a.dot(*b) / (a.magnitude() * b.magnitude());
+            smallest = smallest.min(cos.clamp(-1.0, 1.0).acos());
+        }
+    }
+    smallest
+}
+
+// pairs of nodes drawn as discs of the radius that overlap.
+pub fn node_overlaps<ID: Copy + Ord + Clone + Hash + Eq>(
+    positions: &HashMap<ID, Vect>,
+    radius: Scalar,
+) -> usize {
+    let points: Vec<Vect> = positions.keys().sorted().map(|id| positions[id]).collect();
+    points
+        .iter()
+        .tuple_combinations()
+        .filter(|(a, b)| a.euclid_distance(b) < 2.0 * radius)
+        .count()
+}
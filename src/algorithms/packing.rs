@@ -0,0 +1,109 @@
+use crate::algorithms::{layout_with, LayoutConfig};
+use crate::geometry::{BoundingBox, Scalar, Vect};
+use crate::Graph;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Every connected component is laid out on its own and the bounding boxes are packed into
+// a compact rectangle, so isolated nodes and small pieces stay next to the rest instead of
+// being pushed away by a repulsion no spring holds back.
+
+// node sets of the connected components, each sorted, largest first and then by smallest id.
+pub fn connected_components<T, E, ID: Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+) -> Vec<Vec<ID>> {
+    let mut seen: HashSet<ID> = HashSet::new();
+    let mut components: Vec<Vec<ID>> = Vec::new();
+    for id in g.nodes.keys().sorted() {
+        if seen.contains(id) {
+            continue;
+        }
+        let component: Vec<ID> = g.dfs(id).into_iter().sorted().collect();
+        seen.extend(component.iter().copied());
+        components.push(component);
+    }
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    components
+}
+
+// Shelf packing: tallest first, left to right along rows about as wide as the square root
+// of the total area, with `spacing` between neighbours. Returns the smallest corner of
+// each rectangle, in the order given.
+pub fn pack_rectangles(sizes: &[(Scalar, Scalar)], spacing: Scalar) -> Vec<(Scalar, Scalar)> {
+    let area: Scalar = sizes
+        .iter()
+        .map(|(w, h)| (w + spacing) * (h + spacing))
+        .sum();
+    let widest = sizes.iter().map(|(w, _)| *w).fold(0.0, Scalar::max);
+    let row_width = area.sqrt().max(widest);
+
+    let mut corners = vec![(0.0, 0.0); sizes.len()];
+    let order = (0..sizes.len()).sorted_by(|a, b| sizes[*b].1.total_cmp(&sizes[*a].1));
+    let (mut x, mut y, mut row_height) = (0.0, 0.0, 0.0);
+    for ix in order {
+        let (w, h) = sizes[ix];
+        if x > 0.0 && x + w > row_width {
+            x = 0.0;
+            y += row_height + spacing;
+            row_height = 0.0;
+        }
+        corners[ix] = (x, y);
+        x += w + spacing;
+        row_height = Scalar::max(row_height, h);
+    }
+    corners
+}
+
+pub fn component_layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    config: &LayoutConfig<ID>,
+    spacing: Scalar,
+) -> HashMap<ID, Vect> {
+    let components = connected_components(g);
+    // edges bucketed by the component of their ends, in id order.
+    let component_of: HashMap<ID, usize> = components
+        .iter()
+        .enumerate()
+        .flat_map(|(ix, component)| component.iter().map(move |id| (*id, ix)))
+        .collect();
+    let mut edges: Vec<Vec<ID>> = vec![Vec::new(); components.len()];
+    for edge_id in g.edges.keys().sorted() {
+        edges[component_of[&g.edges[edge_id].left]].push(*edge_id);
+    }
+
+    let mut pieces: Vec<HashMap<ID, Vect>> = Vec::new();
+    for (component, edges) in components.into_iter().zip(edges) {
+        if component.len() == 1 {
+            pieces.push(HashMap::from([(component[0], Vect::new(0., 0., 0.))]));
+            continue;
+        }
+        // the layouts only look at the structure, so the data is left behind.
+        let mut sub: Graph<(), (), ID> = Graph::new();
+        for id in component.iter() {
+            sub.add_node(*id, ());
+        }
+        for edge_id in edges {
+            let edge = &g.edges[&edge_id];
+            sub.add_edge(edge_id, edge.left, edge.right, ()).unwrap();
+        }
+        pieces.push(layout_with(&sub, config).positions);
+    }
+
+    let boxes: Vec<BoundingBox> = pieces
+        .iter()
+        .map(|piece| BoundingBox::of_points(piece.values()).unwrap())
+        .collect();
+    let sizes: Vec<(Scalar, Scalar)> = boxes.iter().map(|b| (b.width(), b.height())).collect();
+    let corners = pack_rectangles(&sizes, spacing);
+
+    let mut positions: HashMap<ID, Vect> = HashMap::new();
+    for ((piece, b), (x, y)) in pieces.into_iter().zip(boxes).zip(corners) {
+        let shift = Vect::new(x - b.min.x, y - b.min.y, 0.);
+        for (id, p) in piece {
+            positions.insert(id, p + shift);
+        }
+    }
+    positions
+}
@@ -0,0 +1,192 @@
+use crate::generators::seeded_rng;
+use crate::geometry::{Scalar, Vect};
+use crate::linalg::{lanczos, lanczos_smallest, SparseMatrix};
+use crate::utils::matrix::IdOrder;
+use crate::Graph;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Laplacian based analysis and layout. Rows follow an IdOrder, parallel edges add up and
+// self loops are left out of the Laplacians. The analysis functions count every edge as
+// weight 1; the matrix builders take a weight function for anything else.
+const SPECTRAL_SEED: u64 = 0x5eed;
+// Lanczos steps before the first convergence check when only a few eigenpairs are wanted.
+const MIN_LANCZOS_STEPS: usize = 200;
+// largest residual |Lv - λv| accepted for a wanted eigenpair.
+const EIGEN_TOLERANCE: Scalar = 1e-9;
+
+fn degree_entries<T, E, ID: Copy + Clone + Hash + Eq, F: Fn(&E) -> Scalar>(
+    g: &Graph<T, E, ID>,
+    order: &IdOrder<ID>,
+    weight: &F,
+) -> (Vec<(usize, usize, Scalar)>, Vec<Scalar>) {
+    let mut entries = Vec::new();
+    let mut degree = vec![0.0; order.len()];
+    for edge in g.edges.values() {
+        let r = order.index_of(&edge.left).unwrap();
+        let c = order.index_of(&edge.right).unwrap();
+        if r == c {
+            continue;
+        }
+        let w = weight(&edge.data);
+        entries.push((r, c, w));
+        entries.push((c, r, w));
+        degree[r] += w;
+        degree[c] += w;
+    }
+    (entries, degree)
+}
+
+pub fn adjacency_matrix<T, E, ID: Copy + Clone + Hash + Eq, F: Fn(&E) -> Scalar>(
+    g: &Graph<T, E, ID>,
+    order: &IdOrder<ID>,
+    weight: F,
+) -> SparseMatrix {
+    let mut entries = Vec::new();
+    for edge in g.edges.values() {
+        let r = order.index_of(&edge.left).unwrap();
+        let c = order.index_of(&edge.right).unwrap();
+        entries.push((r, c, weight(&edge.data)));
+        if r != c {
+            entries.push((c, r, weight(&edge.data)));
+        }
+    }
+    SparseMatrix::from_triplets(order.len(), entries)
+}
+
+// L = D - A
+pub fn laplacian_matrix<T, E, ID: Copy + Clone + Hash + Eq, F: Fn(&E) -> Scalar>(
+    g: &Graph<T, E, ID>,
+    order: &IdOrder<ID>,
+    weight: F,
+) -> SparseMatrix {
+    let (adjacent, degree) = degree_entries(g, order, &weight);
+    let mut entries: Vec<(usize, usize, Scalar)> =
+        adjacent.into_iter().map(|(r, c, w)| (r, c, -w)).collect();
+    entries.extend(degree.iter().enumerate().map(|(r, d)| (r, r, *d)));
+    SparseMatrix::from_triplets(order.len(), entries)
+}
+
+// I - D^-1/2 A D^-1/2, isolated nodes get a zero row.
+pub fn normalized_laplacian_matrix<T, E, ID: Copy + Clone + Hash + Eq, F: Fn(&E) -> Scalar>(
+    g: &Graph<T, E, ID>,
+    order: &IdOrder<ID>,
+    weight: F,
+) -> SparseMatrix {
+    let (adjacent, degree) = degree_entries(g, order, &weight);
+    let scale: Vec<Scalar> = degree
+        .iter()
+        .map(|d| if *d > 0.0 { 1.0 / d.sqrt() } else { 0.0 })
+        .collect();
+    let mut entries: Vec<(usize, usize, Scalar)> = adjacent
+        .into_iter()
+        .map(|(r, c, w)| (r, c, -w * scale[r] * scale[c]))
+        .collect();
+    entries.extend(
+        degree
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| **d > 0.0)
+            .map(|(r, _)| (r, r, 1.0)),
+    );
+    SparseMatrix::from_triplets(order.len(), entries)
+}
+
+// the `count` smallest eigenpairs, each with a residual below EIGEN_TOLERANCE (or exact up
+// to rounding when Lanczos runs to the full size).
+fn smallest_eigenpairs(m: &SparseMatrix, count: usize) -> Vec<(Scalar, Vec<Scalar>)> {
+    let (values, vectors) = lanczos_smallest(
+        m,
+        count,
+        MIN_LANCZOS_STEPS.max(10 * count),
+        EIGEN_TOLERANCE,
+        &mut seeded_rng(SPECTRAL_SEED),
+    );
+    values
+        .into_iter()
+        .zip(vectors)
+        .take(count)
+        .map(|(value, mut v)| {
+            // fix the sign so results are repeatable.
+            if let Some(first) = v.iter().find(|x| x.abs() > 1e-9) {
+                if *first < 0.0 {
+                    v.iter_mut().for_each(|x| *x = -*x);
+                }
+            }
+            (value, v)
+        })
+        .collect()
+}
+
+// every eigenvalue of the Laplacian, ascending. This is a full Lanczos run with a dense
+// basis, O(n^2) memory and O(n^3) time, so it is meant for graphs of a few thousand nodes at
+// most; algebraic_connectivity only computes what it needs.
+pub fn laplacian_spectrum<T, E, ID: Copy + Clone + Hash + Eq + Ord>(
+    g: &Graph<T, E, ID>,
+) -> Vec<Scalar> {
+    let order = IdOrder::from_graph(g);
+    let l = laplacian_matrix(g, &order, |_| 1.0);
+    let mut rng = seeded_rng(SPECTRAL_SEED);
+    lanczos(&l, l.size, &mut rng).0
+}
+
+// second smallest Laplacian eigenvalue, 0 exactly when the graph is disconnected.
+pub fn algebraic_connectivity<T, E, ID: Copy + Clone + Hash + Eq + Ord>(
+    g: &Graph<T, E, ID>,
+) -> Scalar {
+    let order = IdOrder::from_graph(g);
+    let l = laplacian_matrix(g, &order, |_| 1.0);
+    match smallest_eigenpairs(&l, 2).get(1) {
+        Some((value, _)) => value.max(0.0),
+        None => 0.0,
+    }
+}
+
+// eigenvector of the algebraic connectivity, its signs give a spectral bisection.
+pub fn fiedler_vector<T, E, ID: Copy + Clone + Hash + Eq + Ord>(
+    g: &Graph<T, E, ID>,
+) -> HashMap<ID, Scalar> {
+    let order = IdOrder::from_graph(g);
+    let l = laplacian_matrix(g, &order, |_| 1.0);
+    match smallest_eigenpairs(&l, 2).into_iter().nth(1) {
+        Some((_, v)) => order.ids.iter().copied().zip(v).collect(),
+        None => order.ids.iter().map(|id| (*id, 0.0)).collect(),
+    }
+}
+
+// Hall's embedding: the eigenvectors after the constant one give x, y (and z), each
+// stretched so its largest coordinate is `scale`.
+pub fn spectral_layout<T, E, ID: Debug + Copy + Clone + Hash + Eq + Ord>(
+    g: &Graph<T, E, ID>,
+    scale: Scalar,
+    use_z: bool,
+) -> HashMap<ID, Vect> {
+    let order = IdOrder::from_graph(g);
+    let l = laplacian_matrix(g, &order, |_| 1.0);
+    let dims = if use_z { 3 } else { 2 };
+    let pairs = smallest_eigenpairs(&l, dims + 1);
+    let axis = |k: usize| -> Vec<Scalar> {
+        match pairs.get(k + 1) {
+            Some((_, v)) => {
+                let top = v.iter().fold(0.0, |m: Scalar, x| m.max(x.abs()));
+                v.iter()
+                    .map(|x| if top > 0.0 { x * scale / top } else { 0.0 })
+                    .collect()
+            }
+            None => vec![0.0; order.len()],
+        }
+    };
+    let (x, y) = (axis(0), axis(1));
+    let z = if use_z {
+        axis(2)
+    } else {
+        vec![0.0; order.len()]
+    };
+    order
+        .ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (*id, Vect::new(x[i], y[i], z[i])))
+        .collect()
+}
@@ -0,0 +1,636 @@
+use crate::geometry::{Scalar, Vect};
+use crate::Graph;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Layered drawing after Sugiyama, Tagawa & Toda (1981), for graphs read as directed from
+// left to right. The stages are cycle removal (back edges of a DFS are reversed), layer
+// assignment, dummy nodes on every edge spanning more than one layer, crossing minimisation
+// by layer sweeps and finally x coordinates. Layer i is drawn at y = i * layer_spacing, so
+// edges point down the screen. Self loops are ignored when layering.
+const COORDINATE_PASSES: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ranking {
+    // every node as high as its longest path from a source allows.
+    LongestPath,
+    // minimal total edge span, Gansner et al. (1993).
+    NetworkSimplex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ordering {
+    Barycenter,
+    Median,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayeredConfig {
+    pub ranking: Ranking,
+    pub ordering: Ordering,
+    // alternating down and up passes of crossing minimisation.
+    pub sweeps: usize,
+    pub layer_spacing: Scalar,
+    pub node_spacing: Scalar,
+}
+
+impl Default for LayeredConfig {
+    fn default() -> Self {
+        Self {
+            ranking: Ranking::NetworkSimplex,
+            ordering: Ordering::Median,
+            sweeps: 24,
+            layer_spacing: 1.0,
+            node_spacing: 1.0,
+        }
+    }
+}
+
+impl LayeredConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn ranking(mut self, ranking: Ranking) -> Self {
+        self.ranking = ranking;
+        self
+    }
+    pub fn ordering(mut self, ordering: Ordering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+    pub fn sweeps(mut self, sweeps: usize) -> Self {
+        self.sweeps = sweeps;
+        self
+    }
+    pub fn layer_spacing(mut self, spacing: Scalar) -> Self {
+        self.layer_spacing = spacing;
+        self
+    }
+    pub fn node_spacing(mut self, spacing: Scalar) -> Self {
+        self.node_spacing = spacing;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LayeredLayout<ID: Clone + Hash + Eq> {
+    pub positions: HashMap<ID, Vect>,
+    // points between the ends of each long edge, from its left node to its right node.
+    pub bends: HashMap<ID, Vec<Vect>>,
+    pub layers: HashMap<ID, usize>,
+    // edges turned around to break cycles.
+    pub reversed: HashSet<ID>,
+}
+
+// marks the back edges of a depth first search, which leaves the rest acyclic.
+fn remove_cycles(n: usize, edges: &[(usize, usize)]) -> Vec<bool> {
+    let mut out: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    for (ix, (l, r)) in edges.iter().enumerate() {
+        out[*l].push((ix, *r));
+    }
+    // 0 unvisited, 1 on the stack, 2 finished.
+    let mut state = vec![0u8; n];
+    let mut reversed = vec![false; edges.len()];
+    for source in 0..n {
+        if state[source] != 0 {
+            continue;
+        }
+        state[source] = 1;
+        let mut stack: Vec<(usize, usize)> = vec![(source, 0)];
+        while let Some((v, next)) = stack.pop() {
+            if next == out[v].len() {
+                state[v] = 2;
+                continue;
+            }
+            stack.push((v, next + 1));
+            let (ix, w) = out[v][next];
+            match state[w] {
+                0 => {
+                    state[w] = 1;
+                    stack.push((w, 0));
+                }
+                1 => reversed[ix] = true,
+                _ => {}
+            }
+        }
+    }
+    reversed
+}
+
+fn longest_path(n: usize, dag: &[(usize, usize)]) -> Vec<i64> {
+    let mut out: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut indegree = vec![0; n];
+    for (l, r) in dag {
+        out[*l].push(*r);
+        indegree[*r] += 1;
+    }
+    let mut rank = vec![0; n];
+    let mut q: VecDeque<usize> = (0..n).filter(|v| indegree[*v] == 0).collect();
+    while let Some(v) = q.pop_front() {
+        for w in out[v].iter() {
+            rank[*w] = rank[*w].max(rank[v] + 1);
+            indegree[*w] -= 1;
+            if indegree[*w] == 0 {
+                q.push_back(*w);
+            }
+        }
+    }
+    rank
+}
+
+// Spanning forest of the DAG used by network simplex. Tree edges are tight (span exactly
+// one layer); low/lim are the postorder numbers that answer subtree membership in O(1).
+// cut holds the cut value of every tree edge: edges from its tail component to its head
+// component less those going the other way.
+struct Forest<'a> {
+    dag: &'a [(usize, usize)],
+    incident: Vec<Vec<usize>>,
+    rank: Vec<i64>,
+    tree: Vec<bool>,
+    root: Vec<usize>,
+    parent: Vec<Option<usize>>,
+    low: Vec<usize>,
+    lim: Vec<usize>,
+    cut: Vec<i64>,
+}
+
+impl<'a> Forest<'a> {
+    fn slack(&self, e: usize) -> i64 {
+        let (l, r) = self.dag[e];
+        self.rank[r] - self.rank[l] - 1
+    }
+
+    fn other(&self, e: usize, v: usize) -> usize {
+        let (l, r) = self.dag[e];
+        if l == v {
+            r
+        } else {
+            l
+        }
+    }
+
+    // grows a tight tree from every unreached node, shifting the tree to make the closest
+    // outside edge tight whenever it gets stuck.
+    fn feasible_tree(&mut self) {
+        let n = self.rank.len();
+        let mut reached = vec![false; n];
+        for start in 0..n {
+            if reached[start] {
+                continue;
+            }
+            reached[start] = true;
+            let mut members = vec![start];
+            loop {
+                let mut ix = 0;
+                while ix < members.len() {
+                    let v = members[ix];
+                    for e in self.incident[v].iter() {
+                        let w = self.other(*e, v);
+                        if !reached[w] && self.slack(*e) == 0 {
+                            reached[w] = true;
+                            self.tree[*e] = true;
+                            members.push(w);
+                        }
+                    }
+                    ix += 1;
+                }
+                let closest = members
+                    .iter()
+                    .flat_map(|v| self.incident[*v].iter())
+                    .filter(|e| reached[self.dag[**e].0] != reached[self.dag[**e].1])
+                    .min_by_key(|e| self.slack(**e));
+                let e = match closest {
+                    Some(e) => *e,
+                    None => break,
+                };
+                let delta = if reached[self.dag[e].1] {
+                    -self.slack(e)
+                } else {
+                    self.slack(e)
+                };
+                for v in members.iter() {
+                    self.rank[*v] += delta;
+                }
+            }
+            for v in members {
+                self.root[v] = start;
+            }
+        }
+    }
+
+    // re-derives ranks from the tree edges and renumbers low/lim.
+    fn rebuild(&mut self) {
+        let n = self.rank.len();
+        let mut seen = vec![false; n];
+        let mut counter = 0;
+        for start in 0..n {
+            if self.root[start] != start {
+                continue;
+            }
+            seen[start] = true;
+            self.parent[start] = None;
+            let mut stack: Vec<(usize, usize)> = vec![(start, 0)];
+            self.low[start] = counter;
+            while let Some((v, next)) = stack.pop() {
+                if next == self.incident[v].len() {
+                    self.lim[v] = counter;
+                    counter += 1;
+                    continue;
+                }
+                stack.push((v, next + 1));
+                let e = self.incident[v][next];
+                let w = self.other(e, v);
+                if self.tree[e] && !seen[w] {
+                    seen[w] = true;
+                    self.parent[w] = Some(e);
+                    self.rank[w] = if self.dag[e].0 == v {
+                        self.rank[v] + 1
+                    } else {
+                        self.rank[v] - 1
+                    };
+                    self.low[w] = counter;
+                    stack.push((w, 0));
+                }
+            }
+        }
+    }
+
+    fn below(&self, v: usize, top: usize) -> bool {
+        self.low[top] <= self.lim[v] && self.lim[v] <= self.lim[top]
+    }
+
+    // (child end of tree edge e, whether the edge's tail lies in the child's subtree).
+    fn split(&self, e: usize) -> (usize, bool) {
+        let (l, r) = self.dag[e];
+        if self.parent[l] == Some(e) {
+            (l, true)
+        } else {
+            (r, false)
+        }
+    }
+
+    fn in_tail(&self, v: usize, e: usize) -> bool {
+        let (child, tail_below) = self.split(e);
+        self.root[v] == self.root[child] && self.below(v, child) == tail_below
+    }
+
+    fn in_head(&self, v: usize, e: usize) -> bool {
+        let (child, _) = self.split(e);
+        self.root[v] == self.root[child] && !self.in_tail(v, e)
+    }
+
+    // Every cut value in one pass, children before parents. The cut value of the edge above
+    // v follows from the cut values of the tree edges below v and the edges at v, as in
+    // Gansner et al., "A technique for drawing directed graphs", so a pass is O(V + E).
+    fn cut_values(&mut self) {
+        let mut order: Vec<usize> = (0..self.rank.len()).collect();
+        order.sort_by_key(|v| self.lim[*v]);
+        for v in order {
+            let f = match self.parent[v] {
+                Some(f) => f,
+                None => continue,
+            };
+            let v_is_tail = self.dag[f].0 == v;
+            let mut cut = 0;
+            for e in self.incident[v].iter() {
+                let (l, r) = self.dag[*e];
+                let inside = self.below(self.other(*e, v), v);
+                let value = if inside {
+                    if self.tree[*e] {
+                        self.cut[*e] - 1
+                    } else {
+                        -1
+                    }
+                } else {
+                    1
+                };
+                // whether e points the same way across the cut as f.
+                let along = if v_is_tail { r == v } else { l == v };
+                cut += if along == inside { value } else { -value };
+            }
+            self.cut[f] = cut;
+        }
+    }
+}
+
+fn network_simplex(n: usize, dag: &[(usize, usize)]) -> Vec<i64> {
+    let mut incident: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (ix, (l, r)) in dag.iter().enumerate() {
+        incident[*l].push(ix);
+        incident[*r].push(ix);
+    }
+    let mut forest = Forest {
+        dag,
+        incident,
+        rank: longest_path(n, dag),
+        tree: vec![false; dag.len()],
+        root: (0..n).collect(),
+        parent: vec![None; n],
+        low: vec![0; n],
+        lim: vec![0; n],
+        cut: vec![0; dag.len()],
+    };
+    forest.feasible_tree();
+    forest.rebuild();
+    forest.cut_values();
+
+    // degenerate pivots can cycle, so the search is bounded; any tree is still feasible.
+    let max_pivots = 10 * dag.len() + 10;
+    let mut start = 0;
+    for _ in 0..max_pivots {
+        let leaving = (0..dag.len())
+            .map(|i| (start + i) % dag.len())
+            .find(|e| forest.tree[*e] && forest.cut[*e] < 0);
+        let leaving = match leaving {
+            Some(e) => e,
+            None => break,
+        };
+        let entering = (0..dag.len())
+            .filter(|f| {
+                !forest.tree[*f]
+                    && forest.in_head(dag[*f].0, leaving)
+                    && forest.in_tail(dag[*f].1, leaving)
+            })
+            .min_by_key(|f| forest.slack(*f));
+        let entering = match entering {
+            Some(f) => f,
+            None => break,
+        };
+        forest.tree[leaving] = false;
+        forest.tree[entering] = true;
+        forest.rebuild();
+        forest.cut_values();
+        start = leaving + 1;
+    }
+
+    let mut rank = forest.rank;
+    let mut lowest: HashMap<usize, i64> = HashMap::new();
+    for (r, root) in rank.iter().zip(forest.root.iter()) {
+        let low = lowest.entry(*root).or_insert(*r);
+        *low = (*low).min(*r);
+    }
+    for (r, root) in rank.iter_mut().zip(forest.root.iter()) {
+        *r -= lowest[root];
+    }
+    rank
+}
+
+// counts the crossings between neighbouring layers by inversions of the lower ends.
+fn crossings(order: &[Vec<usize>], down: &[Vec<usize>], pos: &[usize]) -> usize {
+    let mut total = 0;
+    for layer in order.iter() {
+        let ends: Vec<usize> = layer
+            .iter()
+            .flat_map(|u| down[*u].iter().map(|v| pos[*v]).sorted())
+            .collect();
+        let width = ends.iter().max().map_or(0, |m| m + 1);
+        let mut counts = vec![0usize; width + 1];
+        for (seen, end) in ends.iter().enumerate() {
+            let mut i = end + 1;
+            let mut not_above = 0;
+            while i > 0 {
+                not_above += counts[i];
+                i -= i & i.wrapping_neg();
+            }
+            total += seen - not_above;
+            let mut i = end + 1;
+            while i <= width {
+                counts[i] += 1;
+                i += i & i.wrapping_neg();
+            }
+        }
+    }
+    total
+}
+
+fn median(mut p: Vec<Scalar>) -> Option<Scalar> {
+    p.sort_by(|a, b| a.total_cmp(b));
+    let m = p.len() / 2;
+    match p.len() {
+        0 => None,
+        2 => Some((p[0] + p[1]) / 2.0),
+        len if len % 2 == 1 => Some(p[m]),
+        len => {
+            let left = p[m - 1] - p[0];
+            let right = p[len - 1] - p[m];
+            if left + right == 0.0 {
+                Some((p[m - 1] + p[m]) / 2.0)
+            } else {
+                Some((p[m - 1] * right + p[m] * left) / (left + right))
+            }
+        }
+    }
+}
+
+// sorts a layer by the positions of its neighbours in the fixed layer; nodes without
+// neighbours keep their slot.
+fn reorder(layer: &mut [usize], fixed: &[Vec<usize>], pos: &mut [usize], ordering: Ordering) {
+    let mut keyed: Vec<(Scalar, usize, usize)> = Vec::new();
+    let mut free_slots: Vec<usize> = Vec::new();
+    for (slot, v) in layer.iter().enumerate() {
+        let p: Vec<Scalar> = fixed[*v].iter().map(|u| pos[*u] as Scalar).collect();
+        let key = match ordering {
+            Ordering::Barycenter if !p.is_empty() => {
+                Some(p.iter().sum::<Scalar>() / p.len() as Scalar)
+            }
+            Ordering::Barycenter => None,
+            Ordering::Median => median(p),
+        };
+        if let Some(k) = key {
+            keyed.push((k, slot, *v));
+            free_slots.push(slot);
+        }
+    }
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+    for (slot, (_, _, v)) in free_slots.into_iter().zip(keyed) {
+        layer[slot] = v;
+    }
+    for (slot, v) in layer.iter().enumerate() {
+        pos[*v] = slot;
+    }
+}
+
+fn reduce_crossings(
+    order: &mut Vec<Vec<usize>>,
+    up: &[Vec<usize>],
+    down: &[Vec<usize>],
+    pos: &mut [usize],
+    config: &LayeredConfig,
+) {
+    let mut best = order.clone();
+    let mut best_crossings = crossings(order, down, pos);
+    for sweep in 0..config.sweeps {
+        if best_crossings == 0 {
+            break;
+        }
+        if sweep % 2 == 0 {
+            for layer in order.iter_mut().skip(1) {
+                reorder(layer, up, pos, config.ordering);
+            }
+        } else {
+            for layer in order.iter_mut().rev().skip(1) {
+                reorder(layer, down, pos, config.ordering);
+            }
+        }
+        let c = crossings(order, down, pos);
+        if c < best_crossings {
+            best_crossings = c;
+            best = order.clone();
+        }
+    }
+    *order = best;
+    for layer in order.iter() {
+        for (slot, v) in layer.iter().enumerate() {
+            pos[*v] = slot;
+        }
+    }
+}
+
+// closest positions to `desired` (least squares) that keep the layer order and spacing,
+// by pool adjacent violators on desired[i] - i * spacing.
+fn place(desired: &[Scalar], spacing: Scalar) -> Vec<Scalar> {
+    let mut blocks: Vec<(Scalar, usize)> = Vec::new();
+    for (i, d) in desired.iter().enumerate() {
+        blocks.push((d - i as Scalar * spacing, 1));
+        while blocks.len() > 1 {
+            let (s2, c2) = blocks[blocks.len() - 1];
+            let (s1, c1) = blocks[blocks.len() - 2];
+            if s1 / (c1 as Scalar) <= s2 / (c2 as Scalar) {
+                break;
+            }
+            blocks.pop();
+            *blocks.last_mut().unwrap() = (s1 + s2, c1 + c2);
+        }
+    }
+    let mut x = Vec::with_capacity(desired.len());
+    for (sum, count) in blocks {
+        let mean = sum / count as Scalar;
+        for _ in 0..count {
+            x.push(mean + x.len() as Scalar * spacing);
+        }
+    }
+    x
+}
+
+fn assign_x(
+    order: &[Vec<usize>],
+    up: &[Vec<usize>],
+    down: &[Vec<usize>],
+    x: &mut [Scalar],
+    spacing: Scalar,
+) {
+    let align = |layer: &[usize], neighbours: &[Vec<usize>], x: &mut [Scalar]| {
+        let desired: Vec<Scalar> = layer
+            .iter()
+            .map(|v| match neighbours[*v].len() {
+                0 => x[*v],
+                len => neighbours[*v].iter().map(|u| x[*u]).sum::<Scalar>() / len as Scalar,
+            })
+            .collect();
+        for (v, new_x) in layer.iter().zip(place(&desired, spacing)) {
+            x[*v] = new_x;
+        }
+    };
+    for _ in 0..COORDINATE_PASSES {
+        for layer in order.iter().skip(1) {
+            align(layer, up, x);
+        }
+        for layer in order.iter().rev().skip(1) {
+            align(layer, down, x);
+        }
+    }
+}
+
+pub fn layered_layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    config: &LayeredConfig,
+) -> LayeredLayout<ID> {
+    let ids: Vec<ID> = g.nodes.keys().copied().sorted().collect();
+    let index: HashMap<ID, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let edge_ids: Vec<ID> = g
+        .edges
+        .keys()
+        .copied()
+        .sorted()
+        .filter(|e| g.edges[e].left != g.edges[e].right)
+        .collect();
+    let edges: Vec<(usize, usize)> = edge_ids
+        .iter()
+        .map(|e| (index[&g.edges[e].left], index[&g.edges[e].right]))
+        .collect();
+
+    let n = ids.len();
+    let reversed = remove_cycles(n, &edges);
+    let dag: Vec<(usize, usize)> = edges
+        .iter()
+        .zip(reversed.iter())
+        .map(|((l, r), rev)| if *rev { (*r, *l) } else { (*l, *r) })
+        .collect();
+    let rank = match config.ranking {
+        Ranking::LongestPath => longest_path(n, &dag),
+        Ranking::NetworkSimplex => network_simplex(n, &dag),
+    };
+
+    // long edges become chains through one dummy node per layer crossed.
+    let mut layer: Vec<usize> = rank.iter().map(|r| *r as usize).collect();
+    let mut up: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut down: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut chains: Vec<Vec<usize>> = Vec::with_capacity(dag.len());
+    for (l, r) in dag.iter() {
+        let mut chain = vec![*l];
+        for level in layer[*l] + 1..layer[*r] {
+            chain.push(layer.len());
+            layer.push(level);
+            up.push(Vec::new());
+            down.push(Vec::new());
+        }
+        chain.push(*r);
+        for (a, b) in chain.iter().tuple_windows() {
+            down[*a].push(*b);
+            up[*b].push(*a);
+        }
+        chains.push(chain);
+    }
+
+    let depth = layer.iter().max().map_or(0, |m| m + 1);
+    let mut order: Vec<Vec<usize>> = vec![Vec::new(); depth];
+    let mut pos = vec![0; layer.len()];
+    for (v, l) in layer.iter().enumerate() {
+        pos[v] = order[*l].len();
+        order[*l].push(v);
+    }
+    reduce_crossings(&mut order, &up, &down, &mut pos, config);
+
+    let mut x: Vec<Scalar> = pos
+        .iter()
+        .map(|p| *p as Scalar * config.node_spacing)
+        .collect();
+    assign_x(&order, &up, &down, &mut x, config.node_spacing);
+    let min_x = x.iter().copied().fold(Scalar::MAX, Scalar::min);
+    let point = |v: usize| Vect::new(x[v] - min_x, layer[v] as Scalar * config.layer_spacing, 0.);
+
+    let mut result = LayeredLayout {
+        positions: HashMap::new(),
+        bends: HashMap::new(),
+        layers: HashMap::new(),
+        reversed: HashSet::new(),
+    };
+    for (v, id) in ids.iter().enumerate() {
+        result.positions.insert(*id, point(v));
+        result.layers.insert(*id, layer[v]);
+    }
+    for ((e, chain), rev) in edge_ids.iter().zip(chains).zip(reversed) {
+        let mut bends: Vec<Vect> = chain[1..chain.len() - 1]
+            .iter()
+            .map(|v| point(*v))
+            .collect();
+        if rev {
+            bends.reverse();
+            result.reversed.insert(*e);
+        }
+        result.bends.insert(*e, bends);
+    }
+    result
+}
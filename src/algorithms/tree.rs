@@ -0,0 +1,177 @@
+use crate::geometry::{Scalar, Vect};
+use crate::graph::GraphError;
+use crate::Graph;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::PI;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Deterministic drawings of the breadth first spanning tree from a root; graphs that are
+// not trees lose their other edges for placement. Components the root cannot reach are
+// drawn as further trees to the right, each rooted at its smallest id.
+
+// node order with parents before children, and children in id order.
+type SpanningTree<ID> = (Vec<ID>, HashMap<ID, Vec<ID>>);
+
+fn spanning_forest<T, E, ID: Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    root: ID,
+) -> Result<Vec<SpanningTree<ID>>, GraphError> {
+    if !g.nodes.contains_key(&root) {
+        return Err(GraphError::new("'root' not in nodes."));
+    }
+    let mut seen: HashSet<ID> = HashSet::new();
+    let mut forest = Vec::new();
+    let roots = std::iter::once(root).chain(g.nodes.keys().copied().sorted());
+    for r in roots {
+        if !seen.insert(r) {
+            continue;
+        }
+        let mut order = vec![r];
+        let mut children: HashMap<ID, Vec<ID>> = HashMap::new();
+        let mut q: VecDeque<ID> = VecDeque::from([r]);
+        while let Some(v) = q.pop_front() {
+            let mut kids = Vec::new();
+            for w in g.neighbors(v)?.into_iter().sorted() {
+                if seen.insert(w) {
+                    kids.push(w);
+                    order.push(w);
+                    q.push_back(w);
+                }
+            }
+            children.insert(v, kids);
+        }
+        forest.push((order, children));
+    }
+    Ok(forest)
+}
+
+// Reingold & Tilford (1981): subtrees are pushed together until their contours are
+// node_spacing apart on every level and parents are centred over their outer children.
+// Returns x relative to the root and the (left, right) extent of the tree.
+fn tidy_x<ID: Copy + Hash + Eq>(
+    order: &[ID],
+    children: &HashMap<ID, Vec<ID>>,
+    node_spacing: Scalar,
+) -> (HashMap<ID, Scalar>, (Scalar, Scalar)) {
+    let mut contours: HashMap<ID, Vec<(Scalar, Scalar)>> = HashMap::new();
+    let mut offset: HashMap<ID, Scalar> = HashMap::new();
+    for v in order.iter().rev() {
+        let kids = &children[v];
+        let mut merged: Vec<(Scalar, Scalar)> = Vec::new();
+        for (ix, c) in kids.iter().enumerate() {
+            let contour = contours.remove(c).unwrap();
+            let shift = if ix == 0 {
+                0.0
+            } else {
+                merged
+                    .iter()
+                    .zip(contour.iter())
+                    .map(|(a, b)| a.1 - b.0 + node_spacing)
+                    .fold(Scalar::MIN, Scalar::max)
+            };
+            offset.insert(*c, shift);
+            for (d, (l, r)) in contour.into_iter().enumerate() {
+                match merged.get_mut(d) {
+                    Some(m) => m.1 = r + shift,
+                    None => merged.push((l + shift, r + shift)),
+                }
+            }
+        }
+        let mid = match (kids.first(), kids.last()) {
+            (Some(first), Some(last)) => (offset[first] + offset[last]) / 2.0,
+            _ => 0.0,
+        };
+        for c in kids {
+            *offset.get_mut(c).unwrap() -= mid;
+        }
+        let mut contour = vec![(0.0, 0.0)];
+        contour.extend(merged.into_iter().map(|(l, r)| (l - mid, r - mid)));
+        contours.insert(*v, contour);
+    }
+
+    let root = order[0];
+    let extent = contours[&root]
+        .iter()
+        .fold((Scalar::MAX, Scalar::MIN), |(lo, hi), (l, r)| {
+            (lo.min(*l), hi.max(*r))
+        });
+    let mut x: HashMap<ID, Scalar> = HashMap::new();
+    x.insert(root, 0.0);
+    for v in order.iter() {
+        for c in children[v].iter() {
+            x.insert(*c, x[v] + offset[c]);
+        }
+    }
+    (x, extent)
+}
+
+// root at the origin, depth d at y = d * level_spacing.
+pub fn tidy_tree_layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    root: ID,
+    node_spacing: Scalar,
+    level_spacing: Scalar,
+) -> Result<HashMap<ID, Vect>, GraphError> {
+    let mut positions: HashMap<ID, Vect> = HashMap::new();
+    let mut right_edge: Option<Scalar> = None;
+    for (order, children) in spanning_forest(g, root)? {
+        let (x, (left, right)) = tidy_x(&order, &children, node_spacing);
+        let shift = right_edge.map_or(0.0, |edge| edge + node_spacing - left);
+        right_edge = Some(shift + right);
+        let mut depth: HashMap<ID, usize> = HashMap::from([(order[0], 0)]);
+        for v in order.iter() {
+            let d = depth[v];
+            for c in children[v].iter() {
+                depth.insert(*c, d + 1);
+            }
+            positions.insert(*v, Vect::new(x[v] + shift, d as Scalar * level_spacing, 0.));
+        }
+    }
+    Ok(positions)
+}
+
+// Root at the centre and depth d on the circle of radius d * ring_spacing. Every subtree
+// gets a wedge of angle proportional to its number of leaves.
+pub fn radial_tree_layout<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq>(
+    g: &Graph<T, E, ID>,
+    root: ID,
+    ring_spacing: Scalar,
+) -> Result<HashMap<ID, Vect>, GraphError> {
+    let mut positions: HashMap<ID, Vect> = HashMap::new();
+    let mut right_edge: Option<Scalar> = None;
+    for (order, children) in spanning_forest(g, root)? {
+        let mut leaves: HashMap<ID, usize> = HashMap::new();
+        for v in order.iter().rev() {
+            let count = children[v].iter().map(|c| leaves[c]).sum::<usize>().max(1);
+            leaves.insert(*v, count);
+        }
+
+        let mut local: HashMap<ID, Vect> = HashMap::new();
+        let mut wedge: HashMap<ID, (Scalar, Scalar, usize)> =
+            HashMap::from([(order[0], (0.0, 2.0 * PI, 0))]);
+        let mut radius: Scalar = 0.0;
+        for v in order.iter() {
+            let (start, end, depth) = wedge[v];
+            let r = depth as Scalar * ring_spacing;
+            radius = radius.max(r);
+            let angle = if depth == 0 { 0.0 } else { (start + end) / 2.0 };
+            local.insert(*v, Vect::new(r * angle.cos(), r * angle.sin(), 0.));
+            let per_leaf = (end - start) / leaves[v] as Scalar;
+            let mut from = start;
+            for c in children[v].iter() {
+                let to = from + per_leaf * leaves[c] as Scalar;
+                wedge.insert(*c, (from, to, depth + 1));
+                from = to;
+            }
+        }
+
+        let shift = right_edge.map_or(0.0, |edge| edge + ring_spacing + radius);
+        right_edge = Some(shift + radius);
+        for (v, p) in local {
+            positions.insert(v, p + Vect::new(shift, 0., 0.));
+        }
+    }
+    Ok(positions)
+}
@@ -0,0 +1,239 @@
+use crate::geometry::{BoundingBox, Scalar, Vect};
+use crate::spatial::KdTree;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, TAU};
+use std::hash::Hash;
+
+// Cameras for the viewer: a pan and zoom transform for flat drawings and an orbit camera for
+// 3D ones. Both map into screen coordinates with y pointing down, and find the nodes drawn
+// under the pointer.
+
+// Orbit camera for the 3D viewer. It circles `target` at `distance`, turned by yaw about the
+// y axis and raised by pitch; at yaw = pitch = 0 it looks down the z axis from +z.
+pub const DEFAULT_FOV: Scalar = FRAC_PI_4;
+// stops the view direction lining up with the y axis, where yaw loses its meaning.
+const PITCH_LIMIT: Scalar = FRAC_PI_2 - 0.01;
+const MIN_DISTANCE: Scalar = 1e-6;
+// points nearer than this fraction of the distance are behind the near plane.
+const NEAR: Scalar = 1e-3;
+// room left around the framed points.
+const FRAME_MARGIN: Scalar = 1.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitCamera {
+    pub target: Vect,
+    pub yaw: Scalar,
+    pub pitch: Scalar,
+    pub distance: Scalar,
+    // vertical field of view in radians.
+    pub fov: Scalar,
+}
+
+// a point in viewport coordinates, y pointing down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projected {
+    pub x: Scalar,
+    pub y: Scalar,
+    // distance along the view direction, larger is further away.
+    pub depth: Scalar,
+}
+
+impl OrbitCamera {
+    pub fn new(target: Vect, distance: Scalar) -> Self {
+        Self {
+            target,
+            yaw: 0.0,
+            pitch: 0.0,
+            distance: distance.max(MIN_DISTANCE),
+            fov: DEFAULT_FOV,
+        }
+    }
+    // Looks at the centre of the points from far enough for all of them to be in view of a
+    // camera with vertical field of view `fov` on a viewport `aspect` times as wide as high,
+    // fitting them to the narrower of the horizontal and vertical fields of view.
+    pub fn framing<'a, I: IntoIterator<Item = &'a Vect>>(
+        points: I,
+        fov: Scalar,
+        aspect: Scalar,
+    ) -> Self {
+        let points: Vec<Vect> = points.into_iter().copied().collect();
+        if points.is_empty() {
+            return Self {
+                fov,
+                ..Self::new(Vect::new(0., 0., 0.), 1.0)
+            };
+        }
+        let sum = points.iter().fold(Vect::new(0., 0., 0.), |acc, p| acc + *p);
+        let centre = sum.scalar_mul(1.0 / points.len() as Scalar);
+        let radius = points
+            .iter()
+            .map(|p| p.euclid_distance(&centre))
+            .fold(0.0, Scalar::max);
+        let radius = if radius > 0.0 { radius } else { 1.0 };
+        let aspect = if aspect > 0.0 { aspect } else { 1.0 };
+        let half = (fov / 2.0).min((aspect * (fov / 2.0).tan()).atan());
+        Self {
+            fov,
+            ..Self::new(centre, FRAME_MARGIN * radius / half.sin())
+        }
+    }
+    pub fn orbit(&mut self, d_yaw: Scalar, d_pitch: Scalar) {
+        self.yaw = (self.yaw + d_yaw).rem_euclid(TAU);
+        self.pitch = (self.pitch + d_pitch).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+    }
+    // factors below 1 move closer.
+    pub fn zoom(&mut self, factor: Scalar) {
+        self.distance = (self.distance * factor).max(MIN_DISTANCE);
+    }
+    pub fn eye(&self) -> Vect {
+        let (sy, cy) = self.yaw.sin_cos();
+        let (sp, cp) = self.pitch.sin_cos();
+        self.target + Vect::new(cp * sy, sp, cp * cy).scalar_mul(self.distance)
+    }
+    // right, up and forward unit vectors.
+    fn basis(&self) -> (Vect, Vect, Vect) {
+        let forward = (self.target - self.eye()).as_unit_vector();
+        let right = (forward * Vect::j()).as_unit_vector();
+        let up = right * forward;
+        (right, up, forward)
+    }
+    // camera coordinates: x right, y up and z the depth along the view direction.
+    pub fn view_coordinates(&self, p: &Vect) -> Vect {
+        let (right, up, forward) = self.basis();
+        let v = *p - self.eye();
+        Vect::new(v.dot(right), v.dot(up), v.dot(forward))
+    }
+    // perspective projection into a width by height viewport, None behind the near plane.
+    pub fn project(&self, p: &Vect, width: Scalar, height: Scalar) -> Option<Projected> {
+        let v = self.view_coordinates(p);
+        if v.z < NEAR * self.distance {
+            return None;
+        }
+        let focal = height / 2.0 / (self.fov / 2.0).tan();
+        Some(Projected {
+            x: width / 2.0 + v.x * focal / v.z,
+            y: height / 2.0 - v.y * focal / v.z,
+            depth: v.z,
+        })
+    }
+    // The node drawn nearest viewport point (x, y) among those within `radius(id, depth)`
+    // of it, so nodes drawn larger can be picked further out; the one in front when several
+    // are equally near.
+    pub fn pick<ID: Copy + Ord + Hash + Eq, F: Fn(&ID, Scalar) -> Scalar>(
+        &self,
+        positions: &HashMap<ID, Vect>,
+        (x, y): (Scalar, Scalar),
+        (width, height): (Scalar, Scalar),
+        radius: F,
+    ) -> Option<ID> {
+        positions
+            .iter()
+            .filter_map(|(id, p)| {
+                let q = self.project(p, width, height)?;
+                let d = (q.x - x).hypot(q.y - y);
+                (d <= radius(id, q.depth)).then_some((d, q.depth, *id))
+            })
+            .sorted_by(|a, b| {
+                a.0.total_cmp(&b.0)
+                    .then(a.1.total_cmp(&b.1))
+                    .then(a.2.cmp(&b.2))
+            })
+            .map(|(_, _, id)| id)
+            .next()
+    }
+}
+
+// screen = layout * scale + offset in x and y, z is dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlatView {
+    pub scale: Scalar,
+    pub offset: (Scalar, Scalar),
+}
+
+const MIN_SCALE: Scalar = 1e-9;
+const MAX_SCALE: Scalar = 1e9;
+
+impl Default for FlatView {
+    fn default() -> Self {
+        Self {
+            scale: 0.015,
+            offset: (400.0, 400.0),
+        }
+    }
+}
+
+impl FlatView {
+    pub fn screen_point(&self, p: &Vect) -> (Scalar, Scalar) {
+        (
+            p.x * self.scale + self.offset.0,
+            p.y * self.scale + self.offset.1,
+        )
+    }
+    pub fn layout_point(&self, x: Scalar, y: Scalar) -> Vect {
+        Vect::new(
+            (x - self.offset.0) / self.scale,
+            (y - self.offset.1) / self.scale,
+            0.,
+        )
+    }
+    pub fn pan(&mut self, dx: Scalar, dy: Scalar) {
+        self.offset = (self.offset.0 + dx, self.offset.1 + dy);
+    }
+    // scales by the factor keeping the layout point under screen point (x, y) in place.
+    pub fn zoom_at(&mut self, factor: Scalar, x: Scalar, y: Scalar) {
+        let anchor = self.layout_point(x, y);
+        self.scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+        self.offset = (x - anchor.x * self.scale, y - anchor.y * self.scale);
+    }
+    // The node drawn nearest screen point (x, y) among those within `radius(id)` pixels of
+    // it, so nodes drawn larger can be picked further out. `reach` bounds every radius.
+    pub fn pick<ID: Copy + Ord + Hash + Eq, F: Fn(&ID) -> Scalar>(
+        &self,
+        index: &KdTree<ID>,
+        (x, y): (Scalar, Scalar),
+        radius: F,
+        reach: Scalar,
+    ) -> Option<ID> {
+        index
+            .within_radius_by_distance(&self.layout_point(x, y), reach / self.scale)
+            .into_iter()
+            .find(|(id, d)| d * self.scale <= radius(id))
+            .map(|(id, _)| id)
+    }
+    // every node drawn inside the screen rectangle with corners a and b, in id order.
+    pub fn pick_box<ID: Copy + Ord + Hash + Eq>(
+        &self,
+        index: &KdTree<ID>,
+        a: (Scalar, Scalar),
+        b: (Scalar, Scalar),
+    ) -> Vec<ID> {
+        let (a, b) = (self.layout_point(a.0, a.1), self.layout_point(b.0, b.1));
+        let area = BoundingBox::new(
+            Vect::new(a.x.min(b.x), a.y.min(b.y), Scalar::NEG_INFINITY),
+            Vect::new(a.x.max(b.x), a.y.max(b.y), Scalar::INFINITY),
+        );
+        index.within_box(&area)
+    }
+    // The largest scale that shows the box inside the screen area with `margin` to spare,
+    // centred. A box without width or height keeps scale 1 in that direction.
+    pub fn fit(b: &BoundingBox, screen: &BoundingBox, margin: Scalar) -> Self {
+        let room = |size: Scalar| (size - 2.0 * margin).max(1.0);
+        let (w, h) = (room(screen.width()), room(screen.height()));
+        let scale = match (b.width() > 0.0, b.height() > 0.0) {
+            (true, true) => (w / b.width()).min(h / b.height()),
+            (true, false) => w / b.width(),
+            (false, true) => h / b.height(),
+            (false, false) => 1.0,
+        }
+        .clamp(MIN_SCALE, MAX_SCALE);
+        let (centre, middle) = (b.centre(), screen.centre());
+        Self {
+            scale,
+            offset: (middle.x - centre.x * scale, middle.y - centre.y * scale),
+        }
+    }
+}
+
+#[cfg(test)]
+mod cameratests;
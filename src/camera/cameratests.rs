@@ -0,0 +1,172 @@
+use crate::camera::{FlatView, OrbitCamera, DEFAULT_FOV};
+use crate::geometry::{BoundingBox, Scalar, Vect};
+use crate::spatial::KdTree;
+use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+
+fn close(a: Scalar, b: Scalar) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn target_projects_to_centre() {
+    let camera = OrbitCamera::new(Vect::new(1., 2., 3.), 10.0);
+    let p = camera
+        .project(&Vect::new(1., 2., 3.), 800.0, 600.0)
+        .unwrap();
+    assert!(close(p.x, 400.0) && close(p.y, 300.0));
+    assert!(close(p.depth, 10.0));
+}
+
+#[test]
+fn projection_keeps_orientation_and_perspective() {
+    let camera = OrbitCamera::new(Vect::new(0., 0., 0.), 10.0);
+    let right = camera
+        .project(&Vect::new(1., 0., 0.), 800.0, 600.0)
+        .unwrap();
+    let up = camera
+        .project(&Vect::new(0., 1., 0.), 800.0, 600.0)
+        .unwrap();
+    assert!(right.x > 400.0 && close(right.y, 300.0));
+    assert!(up.y < 300.0 && close(up.x, 400.0));
+
+    // the same offset looks larger closer to the camera.
+    let near = camera
+        .project(&Vect::new(1., 0., 5.), 800.0, 600.0)
+        .unwrap();
+    assert!(near.depth < right.depth);
+    assert!(near.x - 400.0 > right.x - 400.0);
+}
+
+#[test]
+fn points_behind_the_camera_are_dropped() {
+    let camera = OrbitCamera::new(Vect::new(0., 0., 0.), 10.0);
+    assert!(camera
+        .project(&Vect::new(0., 0., 20.), 800.0, 600.0)
+        .is_none());
+}
+
+#[test]
+fn orbit_moves_the_eye_around_the_target() {
+    let mut camera = OrbitCamera::new(Vect::new(0., 0., 0.), 10.0);
+    camera.orbit(FRAC_PI_2, 0.0);
+    let eye = camera.eye();
+    assert!(close(eye.x, 10.0) && close(eye.y, 0.0) && close(eye.z, 0.0));
+    // seen from +x the z axis points to the right.
+    let p = camera
+        .project(&Vect::new(0., 0., -1.), 800.0, 600.0)
+        .unwrap();
+    assert!(p.x > 400.0);
+
+    camera.orbit(0.0, 10.0);
+    assert!(camera.pitch < FRAC_PI_2);
+    assert!(camera.eye().y > 9.9);
+    camera.zoom(0.5);
+    assert!(close(camera.distance, 5.0));
+}
+
+#[test]
+fn framing_keeps_every_point_in_view() {
+    let points = [
+        Vect::new(-300., 50., 20.),
+        Vect::new(250., -400., -80.),
+        Vect::new(40., 600., 300.),
+        Vect::new(0., 0., -500.),
+    ];
+    for (width, height, fov) in [
+        (800.0, 800.0, DEFAULT_FOV),
+        (300.0, 900.0, 1.0),
+        (1600.0, 400.0, 0.5),
+    ] {
+        let mut camera = OrbitCamera::framing(points.iter(), fov, width / height);
+        assert_eq!(fov, camera.fov);
+        for step in 0..8 {
+            camera.orbit(0.7, 0.2 * (step as Scalar - 4.0));
+            for p in points.iter() {
+                let s = camera.project(p, width, height).unwrap();
+                assert!((0.0..=width).contains(&s.x) && (0.0..=height).contains(&s.y));
+            }
+        }
+    }
+    let single = OrbitCamera::framing([Vect::new(1., 1., 1.)].iter(), DEFAULT_FOV, 1.0);
+    assert!(single.distance > 0.0);
+}
+
+#[test]
+fn flat_view_round_trip_and_zoom() {
+    let mut view = FlatView::default();
+    let p = Vect::new(1000., -2000., 0.);
+    let (x, y) = view.screen_point(&p);
+    assert!(close(x, 415.0) && close(y, 370.0));
+    assert!(view.layout_point(x, y).euclid_distance(&p) < 1e-9);
+
+    view.pan(10., -5.);
+    let (x, y) = view.screen_point(&p);
+    assert!(close(x, 425.0) && close(y, 365.0));
+
+    // the point under the cursor stays put while zooming.
+    let before = view.layout_point(300., 200.);
+    view.zoom_at(2.5, 300., 200.);
+    let after = view.screen_point(&before);
+    assert!(close(after.0, 300.0) && close(after.1, 200.0));
+    assert!(close(view.scale, 0.0375));
+}
+
+#[test]
+fn fit_centres_the_box() {
+    let b = BoundingBox::new(Vect::new(-50., 10., 0.), Vect::new(150., 60., 0.));
+    let screen = BoundingBox::new(Vect::new(0., 0., 0.), Vect::new(800., 600., 0.));
+    let view = FlatView::fit(&b, &screen, 20.0);
+    let (x0, y0) = view.screen_point(&b.min);
+    let (x1, y1) = view.screen_point(&b.max);
+    // width bound: 760 pixels for 200 units.
+    assert!(close(view.scale, 3.8));
+    assert!(close(x0, 20.0) && close(x1, 780.0));
+    assert!(close((y0 + y1) / 2.0, 300.0));
+
+    let point = BoundingBox::new(Vect::new(7., 7., 0.), Vect::new(7., 7., 0.));
+    let view = FlatView::fit(&point, &screen, 20.0);
+    assert_eq!((400.0, 300.0), view.screen_point(&Vect::new(7., 7., 0.)));
+}
+
+#[test]
+fn picking_finds_nodes_under_the_pointer() {
+    let positions = HashMap::from([
+        (1, Vect::new(0., 0., 0.)),
+        (2, Vect::new(100., 0., 0.)),
+        (3, Vect::new(100., 100., 0.)),
+    ]);
+    let index = KdTree::new(&positions);
+    let view = FlatView {
+        scale: 0.5,
+        offset: (10.0, 20.0),
+    };
+    assert_eq!(view.pick(&index, (61.0, 21.0), |_| 3.0, 3.0), Some(2));
+    assert_eq!(view.pick(&index, (35.0, 20.0), |_| 3.0, 3.0), None);
+    // node 3 is drawn large enough to reach the pointer, node 2 is nearer but small.
+    let large = |id: &u32| if *id == 3 { 35.0 } else { 3.0 };
+    assert_eq!(view.pick(&index, (60.0, 40.0), large, 35.0), Some(3));
+    assert_eq!(view.pick(&index, (60.0, 40.0), |_| 3.0, 3.0), None);
+    // corners in either order.
+    assert_eq!(
+        view.pick_box(&index, (70.0, 80.0), (0.0, 0.0)),
+        vec![1, 2, 3]
+    );
+    assert_eq!(view.pick_box(&index, (40.0, 0.0), (70.0, 30.0)), vec![2]);
+
+    // in 3D the node in front wins when two are drawn at the same place.
+    let camera = OrbitCamera::new(Vect::new(0., 0., 0.), 10.0);
+    let stacked = HashMap::from([(1, Vect::new(0., 0., -2.)), (2, Vect::new(0., 0., 2.))]);
+    assert_eq!(
+        camera.pick(&stacked, (400.0, 300.0), (800.0, 600.0), |_, _| 5.0),
+        Some(2)
+    );
+    assert_eq!(
+        camera.pick(&stacked, (450.0, 300.0), (800.0, 600.0), |_, _| 5.0),
+        None
+    );
+    assert_eq!(
+        camera.pick(&stacked, (450.0, 300.0), (800.0, 600.0), |_, _| 60.0),
+        Some(2)
+    );
+}
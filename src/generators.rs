@@ -0,0 +1,41 @@
+use crate::geometry::Vect;
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub mod community;
+pub mod degree;
+pub mod random;
+pub mod structured;
+
+// a graph together with node positions.
+pub type Positioned<T, E, ID> = (Graph<T, E, ID>, HashMap<ID, Vect>);
+// a graph together with the block (community) each node was planted in.
+pub type Partitioned<T, E, ID> = (Graph<T, E, ID>, HashMap<ID, usize>);
+
+// Generators number nodes (and edges) 0, 1, 2, ... so the id type must be able to hold them.
+pub(crate) fn id_from<ID: TryFrom<usize>>(ix: usize) -> ID {
+    match ID::try_from(ix) {
+        Ok(id) => id,
+        Err(_) => panic!("{} does not fit the graph's id type.", ix),
+    }
+}
+
+// adds an edge between the l'th and r'th node, numbering edges in insertion order.
+pub(crate) fn link<T, E, ID: TryFrom<usize> + Copy + Clone + Hash + Eq>(
+    g: &mut Graph<T, E, ID>,
+    l: usize,
+    r: usize,
+    data: E,
+) {
+    let _ = g.add_edge(id_from(g.edges.len()), id_from(l), id_from(r), data);
+}
+
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+#[cfg(test)]
+mod gentests;
@@ -0,0 +1,141 @@
+use crate::generators::{id_from, link, Partitioned};
+use crate::graph::Graph;
+use rand::Rng;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Graphs with planted communities for testing community detection. Blocks are laid out
+// consecutively: with sizes [3, 2] nodes 0..3 are block 0 and 3..5 block 1.
+
+fn blocks_of(sizes: &[usize]) -> Vec<usize> {
+    sizes
+        .iter()
+        .enumerate()
+        .flat_map(|(b, size)| std::iter::repeat_n(b, *size))
+        .collect()
+}
+
+// Stochastic block model: nodes in blocks r and s are joined with probability probs[r][s].
+// probs must be a symmetric sizes.len() x sizes.len() matrix.
+pub fn stochastic_block_model<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    sizes: &[usize],
+    probs: &[Vec<f64>],
+    node_data: FT,
+    edge_data: FE,
+) -> Partitioned<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    let n: usize = sizes.iter().sum();
+    block_model(rng, sizes, probs, &vec![1.0; n], node_data, edge_data)
+}
+
+// Degree corrected block model: each node draws a propensity from a power law with the given
+// exponent (> 2, smaller means more heterogeneous) and the pair probability is scaled by the
+// product of the two propensities, which average 1 in every block. Expected block densities
+// match the plain model while degrees become heavy tailed, as in LFR benchmarks.
+pub fn degree_corrected_block_model<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    sizes: &[usize],
+    probs: &[Vec<f64>],
+    exponent: f64,
+    node_data: FT,
+    edge_data: FE,
+) -> Partitioned<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    assert!(exponent > 2.0, "power law exponent must be above 2.");
+    let blocks = blocks_of(sizes);
+    let mut theta: Vec<f64> = (0..blocks.len())
+        .map(|_| {
+            let u: f64 = rng.gen();
+            (1.0 - u).powf(-1.0 / (exponent - 1.0))
+        })
+        .collect();
+
+    let mut start = 0;
+    for size in sizes {
+        let block = &mut theta[start..start + size];
+        let mean = block.iter().sum::<f64>() / *size as f64;
+        block.iter_mut().for_each(|t| *t /= mean);
+        start += size;
+    }
+    block_model(rng, sizes, probs, &theta, node_data, edge_data)
+}
+
+// Planted partition: 'groups' blocks of 'group_size' nodes, p_in inside a block and p_out
+// between blocks.
+pub fn planted_partition<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    groups: usize,
+    group_size: usize,
+    p_in: f64,
+    p_out: f64,
+    node_data: FT,
+    edge_data: FE,
+) -> Partitioned<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    let sizes = vec![group_size; groups];
+    let probs: Vec<Vec<f64>> = (0..groups)
+        .map(|r| {
+            (0..groups)
+                .map(|s| if r == s { p_in } else { p_out })
+                .collect()
+        })
+        .collect();
+    stochastic_block_model(rng, &sizes, &probs, node_data, edge_data)
+}
+
+fn block_model<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    sizes: &[usize],
+    probs: &[Vec<f64>],
+    theta: &[f64],
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Partitioned<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    assert!(
+        probs.len() == sizes.len() && probs.iter().all(|row| row.len() == sizes.len()),
+        "block probabilities must be a square matrix with one row per block."
+    );
+    let blocks = blocks_of(sizes);
+    let n = blocks.len();
+
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let mut assignment: HashMap<ID, usize> = HashMap::new();
+    for (v, b) in blocks.iter().enumerate() {
+        let data = node_data(rng);
+        g.add_node(id_from(v), data);
+        assignment.insert(id_from(v), *b);
+    }
+
+    for u in 0..n {
+        for v in u + 1..n {
+            let p = probs[blocks[u]][blocks[v]] * theta[u] * theta[v];
+            if rng.gen::<f64>() < p {
+                let data = edge_data(rng);
+                link(&mut g, u, v, data);
+            }
+        }
+    }
+    (g, assignment)
+}
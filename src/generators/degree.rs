@@ -0,0 +1,202 @@
+use crate::generators::{id_from, link};
+use crate::graph::{Graph, GraphError};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::Hash;
+
+// Graphs with a prescribed degree sequence, node i gets degrees[i]. Nodes are numbered 0..n.
+
+// What to do with the self loops and repeated edges a random pairing of stubs produces.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EdgePolicy {
+    // keep them: a multigraph with exactly the requested degrees.
+    Keep,
+    // drop them afterwards: a simple graph, some degrees end up lower than requested.
+    Erase,
+    // redraw the whole pairing until it is simple: a uniformly random simple graph with
+    // exactly the requested degrees (the sequence must be graphical).
+    Reject,
+}
+
+const MAX_ATTEMPTS: usize = 1000;
+
+// Erdős–Gallai: a sequence is the degree sequence of a simple graph iff its sum is even and,
+// sorted in decreasing order, for every k
+//     d1 + ... + dk <= k(k-1) + min(d(k+1), k) + ... + min(dn, k)
+pub fn is_graphical(degrees: &[usize]) -> bool {
+    if !degrees.iter().sum::<usize>().is_multiple_of(2) {
+        return false;
+    }
+    let mut d = degrees.to_vec();
+    d.sort_unstable_by(|a, b| b.cmp(a));
+    let n = d.len();
+
+    let mut suffix: Vec<usize> = vec![0; n + 1];
+    for i in (0..n).rev() {
+        suffix[i] = suffix[i + 1] + d[i];
+    }
+
+    let mut left = 0;
+    for k in 1..=n {
+        left += d[k - 1];
+        // entries after k that are at least k contribute k, the rest contribute themselves.
+        let big = k + d[k..].partition_point(|x| *x >= k);
+        let right = k * (k - 1) + k * (big - k) + suffix[big];
+        if left > right {
+            return false;
+        }
+    }
+    true
+}
+
+fn pair_stubs<R: Rng>(rng: &mut R, degrees: &[usize]) -> Vec<(usize, usize)> {
+    let mut stubs: Vec<usize> = degrees
+        .iter()
+        .enumerate()
+        .flat_map(|(v, d)| std::iter::repeat_n(v, *d))
+        .collect();
+    stubs.shuffle(rng);
+    stubs.chunks(2).map(|p| (p[0], p[1])).collect()
+}
+
+fn is_simple(pairs: &[(usize, usize)]) -> bool {
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    pairs
+        .iter()
+        .all(|(u, v)| u != v && seen.insert((*u.min(v), *u.max(v))))
+}
+
+// Configuration model: each node gets degrees[i] stubs and the stubs are paired at random.
+pub fn configuration_model<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    degrees: &[usize],
+    policy: EdgePolicy,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Result<Graph<T, E, ID>, GraphError>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    if !degrees.iter().sum::<usize>().is_multiple_of(2) {
+        return Err(GraphError::new("degree sequence must have an even sum."));
+    }
+    if policy == EdgePolicy::Reject && !is_graphical(degrees) {
+        return Err(GraphError::new("degree sequence is not graphical."));
+    }
+
+    let mut pairs = pair_stubs(rng, degrees);
+    match policy {
+        EdgePolicy::Keep => {}
+        EdgePolicy::Erase => {
+            let mut seen: HashSet<(usize, usize)> = HashSet::new();
+            pairs.retain(|(u, v)| u != v && seen.insert((*u.min(v), *u.max(v))));
+        }
+        EdgePolicy::Reject => {
+            let mut attempts = 1;
+            while !is_simple(&pairs) {
+                if attempts == MAX_ATTEMPTS {
+                    return Err(GraphError::new(
+                        "no simple pairing found, try EdgePolicy::Erase.",
+                    ));
+                }
+                pairs = pair_stubs(rng, degrees);
+                attempts += 1;
+            }
+        }
+    }
+
+    let mut g: Graph<T, E, ID> = Graph::new();
+    for v in 0..degrees.len() {
+        let data = node_data(rng);
+        g.add_node(id_from(v), data);
+    }
+    for (u, v) in pairs {
+        let data = edge_data(rng);
+        link(&mut g, u, v, data);
+    }
+    Ok(g)
+}
+
+// Random d-regular simple graph on n nodes, following Steger and Wormald: stubs are paired
+// avoiding loops and repeats, and the leftover stubs are reshuffled while a valid pair remains.
+// This is asymptotically uniform and, unlike rejection, stays fast for larger d.
+pub fn random_regular<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    n: usize,
+    d: usize,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Result<Graph<T, E, ID>, GraphError>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    if !(n * d).is_multiple_of(2) {
+        return Err(GraphError::new("n * d must be even."));
+    }
+    if d >= n && d > 0 {
+        return Err(GraphError::new("d must be smaller than n."));
+    }
+
+    let mut edges: Option<Vec<(usize, usize)>> = None;
+    for _ in 0..MAX_ATTEMPTS {
+        edges = try_regular(rng, n, d);
+        if edges.is_some() {
+            break;
+        }
+    }
+    let edges = edges.ok_or_else(|| GraphError::new("no regular graph found."))?;
+
+    let mut g: Graph<T, E, ID> = Graph::new();
+    for v in 0..n {
+        let data = node_data(rng);
+        g.add_node(id_from(v), data);
+    }
+    for (u, v) in edges {
+        let data = edge_data(rng);
+        link(&mut g, u, v, data);
+    }
+    Ok(g)
+}
+
+// leftover stubs are kept in node order so a seeded rng always gives the same graph.
+fn try_regular<R: Rng>(rng: &mut R, n: usize, d: usize) -> Option<Vec<(usize, usize)>> {
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    let mut stubs: Vec<usize> = (0..n).flat_map(|v| std::iter::repeat_n(v, d)).collect();
+
+    while !stubs.is_empty() {
+        let mut leftover: BTreeMap<usize, usize> = BTreeMap::new();
+        stubs.shuffle(rng);
+        for p in stubs.chunks(2) {
+            let (u, v) = (p[0].min(p[1]), p[0].max(p[1]));
+            if u != v && seen.insert((u, v)) {
+                edges.push((u, v));
+            } else {
+                *leftover.entry(u).or_insert(0) += 1;
+                *leftover.entry(v).or_insert(0) += 1;
+            }
+        }
+        // give up unless some leftover pair could still be joined.
+        let nodes: Vec<usize> = leftover.keys().copied().collect();
+        let possible = nodes.iter().enumerate().any(|(i, u)| {
+            nodes[i + 1..]
+                .iter()
+                .any(|v| !seen.contains(&(*u.min(v), *u.max(v))))
+        });
+        if !leftover.is_empty() && !possible {
+            return None;
+        }
+        stubs = leftover
+            .iter()
+            .flat_map(|(v, c)| std::iter::repeat_n(*v, *c))
+            .collect();
+    }
+    Some(edges)
+}
@@ -0,0 +1,363 @@
+use crate::generators::community::{
+    degree_corrected_block_model, planted_partition, stochastic_block_model,
+};
+use crate::generators::degree::{configuration_model, is_graphical, random_regular, EdgePolicy};
+use crate::generators::random::{
+    barabasi_albert, erdos_renyi_gnm, erdos_renyi_gnp, geometric_graph, random_geometric,
+    random_graph, watts_strogatz,
+};
+use crate::generators::seeded_rng;
+use crate::generators::structured::{
+    binary_tree, complete_bipartite_graph, complete_graph, cycle_graph, desargues_graph,
+    dodecahedral_graph, frucht_graph, grid_2d_graph, grid_3d_graph, heawood_graph, hypercube_graph,
+    k_ary_tree, mobius_kantor_graph, pappus_graph, path_graph, petersen_graph, star_graph,
+    wheel_graph,
+};
+use crate::geometry::Vect;
+use crate::graph::Graph;
+use crate::utils::create_seeded_random_graph;
+use itertools::Itertools;
+use rand::Rng;
+use std::collections::HashMap;
+
+fn edge_pairs<T, E>(g: &Graph<T, E, u32>) -> Vec<(u32, u32)> {
+    g.edges
+        .values()
+        .map(|e| (e.left, e.right))
+        .sorted()
+        .collect()
+}
+
+#[test]
+fn seeded_graphs_repeat() {
+    let g1: Graph<i32, i32, i32> = create_seeded_random_graph(7, 10, 20, 1, 10, 0, 5);
+    let g2: Graph<i32, i32, i32> = create_seeded_random_graph(7, 10, 20, 1, 10, 0, 5);
+    assert_eq!(g1.nodes, g2.nodes);
+    assert_eq!(g1.edges, g2.edges);
+}
+
+#[test]
+fn random_graph_is_generic() {
+    let mut rng = seeded_rng(42);
+    let g: Graph<String, f64, u32> = random_graph(
+        &mut rng,
+        5,
+        6,
+        |r| format!("n{}", r.gen_range(0..3)),
+        |r| r.gen_range(0.0..1.0),
+    );
+    // all requested nodes are created, ids 0..n
+    assert_eq!(5, g.nodes.len());
+    assert!((0..5).all(|id| g.nodes.contains_key(&id)));
+    assert!(g.nodes.values().all(|n| n.data.starts_with('n')));
+    assert!(g.edges.values().all(|e| e.left != e.right && e.data < 1.0));
+
+    let mut rng = seeded_rng(42);
+    let again: Graph<String, f64, u32> = random_graph(
+        &mut rng,
+        5,
+        6,
+        |r| format!("n{}", r.gen_range(0..3)),
+        |r| r.gen_range(0.0..1.0),
+    );
+    assert_eq!(edge_pairs(&g), edge_pairs(&again));
+}
+
+#[test]
+fn tiny_random_graphs() {
+    let g: Graph<(), (), u8> = random_graph(&mut seeded_rng(1), 1, 1, |_| (), |_| ());
+    assert_eq!(1, g.nodes.len());
+    assert!(g.edges.is_empty());
+}
+
+fn degrees<T, E>(g: &Graph<T, E, u32>) -> Vec<usize> {
+    g.nodes
+        .keys()
+        .sorted()
+        .map(|id| g.nodes.get(id).unwrap().edges.len())
+        .collect()
+}
+
+#[test]
+fn erdos_renyi() {
+    let g: Graph<(), (), u32> = erdos_renyi_gnp(&mut seeded_rng(3), 200, 0.05, |_| (), |_| ());
+    assert_eq!(200, g.nodes.len());
+    // expected 995 edges
+    assert!((850..1150).contains(&g.edges.len()));
+    assert!(g.edges.values().all(|e| e.left != e.right));
+    assert_eq!(g.edges.len(), edge_pairs(&g).into_iter().dedup().count());
+
+    let full: Graph<(), (), u32> = erdos_renyi_gnp(&mut seeded_rng(3), 6, 1.0, |_| (), |_| ());
+    assert_eq!(15, full.edges.len());
+
+    let g: Graph<(), (), u32> = erdos_renyi_gnm(&mut seeded_rng(3), 50, 300, |_| (), |_| ());
+    assert_eq!(300, g.edges.len());
+    assert!(g
+        .edges
+        .values()
+        .all(|e| e.left != e.right && e.left < 50 && e.right < 50));
+    let pairs: Vec<(u32, u32)> = g
+        .edges
+        .values()
+        .map(|e| (e.left.min(e.right), e.left.max(e.right)))
+        .sorted()
+        .dedup()
+        .collect();
+    assert_eq!(300, pairs.len());
+
+    let capped: Graph<(), (), u32> = erdos_renyi_gnm(&mut seeded_rng(3), 5, 100, |_| (), |_| ());
+    assert_eq!(10, capped.edges.len());
+}
+
+#[test]
+fn barabasi_albert_degrees() {
+    let g: Graph<(), (), u32> = barabasi_albert(&mut seeded_rng(5), 500, 3, |_| (), |_| ());
+    assert_eq!((500 - 3) * 3, g.edges.len());
+    let d = degrees(&g);
+    // preferential attachment grows hubs well above the mean degree of ~6
+    assert!(*d.iter().max().unwrap() > 30);
+    assert!(d.iter().skip(3).all(|x| *x >= 3));
+}
+
+#[test]
+fn watts_strogatz_ring() {
+    let ring: Graph<(), (), u32> = watts_strogatz(&mut seeded_rng(9), 20, 4, 0.0, |_| (), |_| ());
+    assert_eq!(40, ring.edges.len());
+    assert!(degrees(&ring).iter().all(|d| *d == 4));
+    assert!(ring.connected(&0, &19) && ring.connected(&0, &18));
+
+    let g: Graph<(), (), u32> = watts_strogatz(&mut seeded_rng(9), 100, 6, 0.3, |_| (), |_| ());
+    assert_eq!(300, g.edges.len());
+    assert!(g.edges.values().all(|e| e.left != e.right));
+    assert_eq!(
+        300,
+        edge_pairs(&g)
+            .into_iter()
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .sorted()
+            .dedup()
+            .count()
+    );
+}
+
+#[test]
+fn random_geometric_edges_are_short() {
+    let (g, positions): (Graph<(), f64, u32>, _) =
+        random_geometric(&mut seeded_rng(11), 100, 0.2, false, |_| (), |d| d);
+    assert_eq!(100, positions.len());
+    assert!(!g.edges.is_empty());
+    for e in g.edges.values() {
+        let d = positions[&e.left].euclid_distance(&positions[&e.right]);
+        assert!(d <= 0.2 && (d - e.data).abs() < 1e-12);
+    }
+    assert!(positions.values().all(|p| p.z == 0.0));
+}
+
+#[test]
+fn geometric_graph_joins_close_pairs() {
+    let (_, positions): (Graph<(), f64, u32>, _) =
+        random_geometric(&mut seeded_rng(12), 80, 0.1, true, |_| (), |d| d);
+    let g: Graph<u32, f64, u32> = geometric_graph(&positions, 0.25, |id| *id * 2, |d| d);
+    assert_eq!(80, g.nodes.len());
+    assert_eq!(14, g.nodes[&7].data);
+    for (a, b) in (0..80u32).tuple_combinations() {
+        let d = positions[&a].euclid_distance(&positions[&b]);
+        assert_eq!(d <= 0.25, g.connected(&a, &b));
+    }
+}
+
+type Fixture = Graph<(), (), u32>;
+
+#[test]
+fn structured_sizes() {
+    let sizes = |(g, p): (Fixture, HashMap<u32, Vect>)| {
+        assert_eq!(g.nodes.len(), p.len());
+        assert!(g.edges.values().all(|e| e.left != e.right));
+        (g.nodes.len(), g.edges.len())
+    };
+    assert_eq!((6, 15), sizes(complete_graph(6)));
+    assert_eq!((7, 12), sizes(complete_bipartite_graph(3, 4)));
+    assert_eq!((5, 4), sizes(path_graph(5)));
+    assert_eq!((5, 5), sizes(cycle_graph(5)));
+    assert_eq!((2, 1), sizes(cycle_graph(2)));
+    assert_eq!((6, 5), sizes(star_graph(5)));
+    assert_eq!((6, 10), sizes(wheel_graph(5)));
+    assert_eq!((12, 17), sizes(grid_2d_graph(4, 3, false)));
+    assert_eq!((12, 24), sizes(grid_2d_graph(4, 3, true)));
+    assert_eq!((27, 54), sizes(grid_3d_graph(3, 3, 3, false)));
+    assert_eq!((16, 32), sizes(hypercube_graph(4)));
+    assert_eq!((15, 14), sizes(binary_tree(3)));
+    assert_eq!((13, 12), sizes(k_ary_tree(3, 2)));
+    assert_eq!((10, 15), sizes(petersen_graph()));
+    assert_eq!((14, 21), sizes(heawood_graph()));
+    assert_eq!((16, 24), sizes(mobius_kantor_graph()));
+    assert_eq!((18, 27), sizes(pappus_graph()));
+    assert_eq!((20, 30), sizes(desargues_graph()));
+    assert_eq!((20, 30), sizes(dodecahedral_graph()));
+    assert_eq!((12, 18), sizes(frucht_graph()));
+}
+
+#[test]
+fn named_graphs_are_cubic() {
+    for (g, _) in [
+        petersen_graph::<(), (), u32>(),
+        heawood_graph(),
+        pappus_graph(),
+        dodecahedral_graph(),
+        frucht_graph(),
+    ] {
+        assert!(degrees(&g).iter().all(|d| *d == 3));
+    }
+}
+
+#[test]
+fn structured_positions() {
+    let (g, p): (Fixture, _) = grid_2d_graph(3, 2, false);
+    assert_eq!(Vect::new(2., 1., 0.), p[&5]);
+    assert!(g.connected(&4, &5) && g.connected(&2, &5) && !g.connected(&2, &3));
+
+    let (g, p): (Fixture, _) = binary_tree(2);
+    assert!(g.connected(&0, &1) && g.connected(&0, &2) && g.connected(&2, &6));
+    assert_eq!(Vect::new(0., 0., 0.), p[&0]);
+    assert_eq!(1.0, p[&4].x - p[&3].x);
+
+    let (g, p): (Fixture, _) = hypercube_graph(3);
+    assert!(g.connected(&0, &4) && !g.connected(&0, &3));
+    // every edge is one of the unit axes
+    for e in g.edges.values() {
+        assert!((p[&e.left].euclid_distance(&p[&e.right]) - 1.0).abs() < 1e-9);
+    }
+}
+
+// fraction of edges that stay inside a block
+fn internal_fraction<T, E>(g: &Graph<T, E, u32>, blocks: &HashMap<u32, usize>) -> f64 {
+    let inside = g
+        .edges
+        .values()
+        .filter(|e| blocks[&e.left] == blocks[&e.right])
+        .count();
+    inside as f64 / g.edges.len() as f64
+}
+
+#[test]
+fn planted_partition_blocks() {
+    let (g, blocks): (Fixture, _) =
+        planted_partition(&mut seeded_rng(21), 4, 25, 0.4, 0.01, |_| (), |_| ());
+    assert_eq!(100, g.nodes.len());
+    assert_eq!(100, blocks.len());
+    assert_eq!(0, blocks[&0]);
+    assert_eq!(3, blocks[&99]);
+    assert!(internal_fraction(&g, &blocks) > 0.8);
+}
+
+#[test]
+fn block_model_densities() {
+    let probs = vec![vec![0.0, 0.5], vec![0.5, 0.0]];
+    let (g, blocks): (Fixture, _) =
+        stochastic_block_model(&mut seeded_rng(2), &[10, 30], &probs, |_| (), |_| ());
+    // bipartite: nothing inside a block
+    assert_eq!(0.0, internal_fraction(&g, &blocks));
+    assert!((100..200).contains(&g.edges.len()));
+}
+
+#[test]
+fn degree_corrected_is_heterogeneous() {
+    let probs = vec![vec![0.1, 0.005], vec![0.005, 0.1]];
+    let (g, blocks): (Fixture, _) =
+        degree_corrected_block_model(&mut seeded_rng(8), &[200, 200], &probs, 2.5, |_| (), |_| ());
+    assert!(internal_fraction(&g, &blocks) > 0.8);
+    let d = degrees(&g);
+    let mean = d.iter().sum::<usize>() as f64 / d.len() as f64;
+    assert!(*d.iter().max().unwrap() as f64 > 3.0 * mean);
+}
+
+#[test]
+fn erdos_gallai() {
+    assert!(is_graphical(&[]));
+    assert!(is_graphical(&[3, 3, 3, 3]));
+    assert!(is_graphical(&[2, 2, 2]));
+    assert!(is_graphical(&[1, 1, 0]));
+    assert!(!is_graphical(&[1, 1, 1]));
+    assert!(!is_graphical(&[3, 3, 1, 1]));
+    assert!(is_graphical(&[4, 1, 1, 1, 1, 0]));
+    assert!(!is_graphical(&[4, 4, 1, 1, 1, 1]));
+    assert!(!is_graphical(&[3, 2]));
+}
+
+#[test]
+fn configuration_model_policies() {
+    let seq = [3, 3, 2, 2, 2, 1, 1, 4, 2];
+    let g: Fixture =
+        configuration_model(&mut seeded_rng(4), &seq, EdgePolicy::Keep, |_| (), |_| ()).unwrap();
+    // a self loop is listed twice in its node's edges so degrees are exact
+    assert_eq!(seq.to_vec(), degrees(&g));
+
+    let g: Fixture =
+        configuration_model(&mut seeded_rng(4), &seq, EdgePolicy::Reject, |_| (), |_| ()).unwrap();
+    assert_eq!(seq.to_vec(), degrees(&g));
+    assert!(g.edges.values().all(|e| e.left != e.right));
+
+    let g: Fixture = configuration_model(
+        &mut seeded_rng(4),
+        &[8, 8, 1, 1],
+        EdgePolicy::Erase,
+        |_| (),
+        |_| (),
+    )
+    .unwrap();
+    assert!(g.edges.values().all(|e| e.left != e.right));
+    assert!(g.edges.len() <= 3);
+
+    assert!(configuration_model::<(), (), u32, _, _, _>(
+        &mut seeded_rng(4),
+        &[1, 1, 1],
+        EdgePolicy::Keep,
+        |_| (),
+        |_| ()
+    )
+    .is_err());
+    assert!(configuration_model::<(), (), u32, _, _, _>(
+        &mut seeded_rng(4),
+        &[3, 3, 1, 1],
+        EdgePolicy::Reject,
+        |_| (),
+        |_| ()
+    )
+    .is_err());
+}
+
+#[test]
+fn random_regular_graphs() {
+    for (n, d) in [(10, 3), (50, 4), (30, 12)] {
+        let g: Fixture = random_regular(&mut seeded_rng(6), n, d, |_| (), |_| ()).unwrap();
+        assert_eq!(n * d / 2, g.edges.len());
+        assert!(degrees(&g).iter().all(|x| *x == d));
+        let pairs: Vec<(u32, u32)> = g
+            .edges
+            .values()
+            .map(|e| (e.left.min(e.right), e.left.max(e.right)))
+            .sorted()
+            .dedup()
+            .collect();
+        assert_eq!(g.edges.len(), pairs.len());
+        assert!(pairs.iter().all(|(a, b)| a != b));
+    }
+    assert!(
+        random_regular::<(), (), u32, _, _, _>(&mut seeded_rng(6), 5, 3, |_| (), |_| ()).is_err()
+    );
+}
+
+#[test]
+fn random_regular_is_reproducible() {
+    let edges = |seed: u64| -> Vec<(u32, u32, u32)> {
+        let g: Fixture = random_regular(&mut seeded_rng(seed), 60, 5, |_| (), |_| ()).unwrap();
+        g.edges
+            .values()
+            .map(|e| (e.id, e.left, e.right))
+            .sorted()
+            .collect()
+    };
+    for seed in 0..5 {
+        assert_eq!(edges(seed), edges(seed));
+    }
+}
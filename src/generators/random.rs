@@ -0,0 +1,327 @@
+use crate::generators::{id_from, link, Positioned};
+use crate::geometry::{Scalar, Vect};
+use crate::graph::Graph;
+use crate::spatial::KdTree;
+use itertools::Itertools;
+use rand::seq::index::sample;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+// All generators here take the rng explicitly, and hand it to the node and edge data closures,
+// so a seeded rng (generators::seeded_rng) reproduces the whole graph. Nodes are numbered 0..n.
+
+fn add_nodes<T, E, ID, R, FT>(g: &mut Graph<T, E, ID>, rng: &mut R, n: usize, node_data: &mut FT)
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    FT: FnMut(&mut R) -> T,
+{
+    for x in 0..n {
+        let data = node_data(rng);
+        g.add_node(id_from(x), data);
+    }
+}
+
+// Between min_nodes (inclusive) and max_nodes (exclusive) nodes with about two random edges per
+// node; self loops and repeated pairs are skipped.
+pub fn random_graph<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    min_nodes: usize,
+    max_nodes: usize,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Graph<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let nodes = if min_nodes < max_nodes {
+        rng.gen_range(min_nodes..max_nodes)
+    } else {
+        min_nodes
+    };
+    add_nodes(&mut g, rng, nodes, &mut node_data);
+    if nodes < 2 {
+        return g;
+    }
+
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    for _ in 0..2 * nodes {
+        let l = rng.gen_range(0..nodes);
+        let r = rng.gen_range(0..nodes);
+        if l == r || !seen.insert((l.min(r), l.max(r))) {
+            continue;
+        }
+        let data = edge_data(rng);
+        link(&mut g, l, r, data);
+    }
+    g
+}
+
+// Erdős–Rényi G(n, p): every pair is joined with probability p. Uses Batagelj and Brandes'
+// geometric skipping so sparse graphs cost O(n + m) rather than O(n²).
+pub fn erdos_renyi_gnp<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    n: usize,
+    p: f64,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Graph<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    add_nodes(&mut g, rng, n, &mut node_data);
+    if p <= 0.0 {
+        return g;
+    }
+    if p >= 1.0 {
+        for v in 1..n {
+            for w in 0..v {
+                let data = edge_data(rng);
+                link(&mut g, v, w, data);
+            }
+        }
+        return g;
+    }
+
+    let lp = (1.0 - p).ln();
+    let mut v: usize = 1;
+    let mut w: i64 = -1;
+    while v < n {
+        let r: f64 = rng.gen();
+        w += 1 + ((1.0 - r).ln() / lp).floor() as i64;
+        while w >= v as i64 && v < n {
+            w -= v as i64;
+            v += 1;
+        }
+        if v < n {
+            let data = edge_data(rng);
+            link(&mut g, v, w as usize, data);
+        }
+    }
+    g
+}
+
+// Erdős–Rényi G(n, m): m distinct edges chosen uniformly (capped at n(n-1)/2).
+pub fn erdos_renyi_gnm<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    n: usize,
+    m: usize,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Graph<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    add_nodes(&mut g, rng, n, &mut node_data);
+    let pairs = n * n.saturating_sub(1) / 2;
+    let chosen = sample(rng, pairs, m.min(pairs));
+    for k in chosen.iter() {
+        // k enumerates the pairs (v, w) with w < v row by row.
+        let mut v = ((1.0 + (1.0 + 8.0 * k as f64).sqrt()) / 2.0).floor() as usize;
+        while v * (v - 1) / 2 > k {
+            v -= 1;
+        }
+        while (v + 1) * v / 2 <= k {
+            v += 1;
+        }
+        let w = k - v * (v - 1) / 2;
+        let data = edge_data(rng);
+        link(&mut g, v, w, data);
+    }
+    g
+}
+
+// Barabási–Albert preferential attachment: each new node attaches to m existing nodes chosen
+// with probability proportional to their degree. The first m nodes start unconnected.
+pub fn barabasi_albert<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    n: usize,
+    m: usize,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Graph<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    add_nodes(&mut g, rng, n, &mut node_data);
+    if m == 0 || m >= n {
+        return g;
+    }
+
+    // every node appears once per incident edge, so sampling from it is degree proportional.
+    let mut repeated: Vec<usize> = Vec::new();
+    let mut targets: Vec<usize> = (0..m).collect();
+    for source in m..n {
+        for t in targets.iter() {
+            let data = edge_data(rng);
+            link(&mut g, source, *t, data);
+        }
+        repeated.extend(targets.iter());
+        repeated.extend(std::iter::repeat_n(source, m));
+
+        let mut chosen: HashSet<usize> = HashSet::new();
+        targets.clear();
+        while targets.len() < m {
+            let t = repeated[rng.gen_range(0..repeated.len())];
+            if chosen.insert(t) {
+                targets.push(t);
+            }
+        }
+    }
+    g
+}
+
+// Watts–Strogatz small world: a ring where each node is joined to its k nearest neighbours
+// (k/2 either side), then every edge is rewired to a random endpoint with probability beta.
+pub fn watts_strogatz<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    n: usize,
+    k: usize,
+    beta: f64,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Graph<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(&mut R) -> E,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    add_nodes(&mut g, rng, n, &mut node_data);
+    let half = (k / 2).min(n.saturating_sub(1) / 2);
+
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    let mut adjacent: HashSet<(usize, usize)> = HashSet::new();
+    let mut degree: Vec<usize> = vec![0; n];
+    for j in 1..=half {
+        for u in 0..n {
+            let v = (u + j) % n;
+            edges.push((u, v));
+            adjacent.insert((u.min(v), u.max(v)));
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+    }
+
+    for e in edges.iter_mut() {
+        let (u, v) = *e;
+        if !rng.gen_bool(beta.clamp(0.0, 1.0)) {
+            continue;
+        }
+        // a node joined to everything cannot be rewired.
+        if degree[u] >= n - 1 {
+            continue;
+        }
+        let w = loop {
+            let w = rng.gen_range(0..n);
+            if w != u && !adjacent.contains(&(u.min(w), u.max(w))) {
+                break w;
+            }
+        };
+        adjacent.remove(&(u.min(v), u.max(v)));
+        adjacent.insert((u.min(w), u.max(w)));
+        degree[v] -= 1;
+        degree[w] += 1;
+        *e = (u, w);
+    }
+
+    for (u, v) in edges {
+        let data = edge_data(rng);
+        link(&mut g, u, v, data);
+    }
+    g
+}
+
+// index pairs u < v at most radius apart with their distance, in order.
+fn pairs_within(points: &[Vect], radius: Scalar) -> Vec<(usize, usize, Scalar)> {
+    let tree = KdTree::from_points(points.iter().copied().enumerate().collect());
+    let mut pairs = Vec::new();
+    for (u, p) in points.iter().enumerate() {
+        for v in tree.within_radius(p, radius) {
+            if v > u {
+                pairs.push((u, v, p.euclid_distance(&points[v])));
+            }
+        }
+    }
+    pairs
+}
+
+// Random geometric graph: n points uniform in the unit square (cube when use_z is set), joined
+// when closer than radius. Edge data is built from the edge's length.
+pub fn random_geometric<T, E, ID, R, FT, FE>(
+    rng: &mut R,
+    n: usize,
+    radius: Scalar,
+    use_z: bool,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Positioned<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: Rng,
+    FT: FnMut(&mut R) -> T,
+    FE: FnMut(Scalar) -> E,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    add_nodes(&mut g, rng, n, &mut node_data);
+
+    let mut points: Vec<Vect> = Vec::with_capacity(n);
+    for _ in 0..n {
+        let x = rng.gen_range(0.0..1.0);
+        let y = rng.gen_range(0.0..1.0);
+        let z = if use_z { rng.gen_range(0.0..1.0) } else { 0.0 };
+        points.push(Vect::new(x, y, z));
+    }
+
+    for (u, v, d) in pairs_within(&points, radius) {
+        link(&mut g, u, v, edge_data(d));
+    }
+    let positions = points
+        .into_iter()
+        .enumerate()
+        .map(|(ix, p)| (id_from(ix), p))
+        .collect();
+    (g, positions)
+}
+
+// Joins every pair of the given positions at most radius apart, in id order; nodes keep
+// their ids and edge data is built from the edge's length.
+pub fn geometric_graph<T, E, ID, FT, FE>(
+    positions: &HashMap<ID, Vect>,
+    radius: Scalar,
+    mut node_data: FT,
+    mut edge_data: FE,
+) -> Graph<T, E, ID>
+where
+    ID: TryFrom<usize> + Copy + Ord + Clone + Hash + Eq,
+    FT: FnMut(&ID) -> T,
+    FE: FnMut(Scalar) -> E,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let ids: Vec<ID> = positions.keys().copied().sorted().collect();
+    for id in ids.iter() {
+        g.add_node(*id, node_data(id));
+    }
+    let points: Vec<Vect> = ids.iter().map(|id| positions[id]).collect();
+    for (u, v, d) in pairs_within(&points, radius) {
+        let _ = g.add_edge(id_from(g.edges.len()), ids[u], ids[v], edge_data(d));
+    }
+    g
+}
@@ -0,0 +1,336 @@
+use crate::generators::{id_from, link, Positioned};
+use crate::geometry::{Scalar, Vect};
+use crate::graph::Graph;
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use std::hash::Hash;
+
+// Deterministic graphs with default node and edge data, returned with canonical positions
+// (unit spacing) that can be used directly or as a starting layout. Nodes are numbered 0..n.
+
+fn build<T, E, ID>(
+    points: Vec<Vect>,
+    edges: impl IntoIterator<Item = (usize, usize)>,
+) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    for ix in 0..points.len() {
+        g.add_node(id_from(ix), T::default());
+    }
+    for (l, r) in edges {
+        link(&mut g, l, r, E::default());
+    }
+    let positions = points
+        .into_iter()
+        .enumerate()
+        .map(|(ix, p)| (id_from(ix), p))
+        .collect();
+    (g, positions)
+}
+
+fn circle(n: usize, radius: Scalar) -> Vec<Vect> {
+    (0..n)
+        .map(|i| {
+            let a = 2.0 * PI * i as Scalar / n as Scalar;
+            Vect::new(radius * a.cos(), radius * a.sin(), 0.)
+        })
+        .collect()
+}
+
+pub fn complete_graph<T, E, ID>(n: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let edges = (0..n).flat_map(|u| (u + 1..n).map(move |v| (u, v)));
+    build(circle(n, 1.), edges)
+}
+
+// nodes 0..a form one side, a..a+b the other.
+pub fn complete_bipartite_graph<T, E, ID>(a: usize, b: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let mut points: Vec<Vect> = (0..a).map(|i| Vect::new(0., i as Scalar, 0.)).collect();
+    points.extend((0..b).map(|j| Vect::new(1., j as Scalar, 0.)));
+    let edges = (0..a).flat_map(|u| (a..a + b).map(move |v| (u, v)));
+    build(points, edges)
+}
+
+pub fn path_graph<T, E, ID>(n: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let points = (0..n).map(|i| Vect::new(i as Scalar, 0., 0.)).collect();
+    build(points, (1..n).map(|v| (v - 1, v)))
+}
+
+pub fn cycle_graph<T, E, ID>(n: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let edges: Vec<(usize, usize)> = match n {
+        0 | 1 => Vec::new(),
+        2 => vec![(0, 1)],
+        _ => (0..n).map(|v| (v, (v + 1) % n)).collect(),
+    };
+    build(circle(n, 1.), edges)
+}
+
+// node 0 is the centre, joined to n leaves.
+pub fn star_graph<T, E, ID>(n: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let mut points = vec![Vect::new(0., 0., 0.)];
+    points.extend(circle(n, 1.));
+    build(points, (1..=n).map(|v| (0, v)))
+}
+
+// node 0 is the hub of a rim of n nodes.
+pub fn wheel_graph<T, E, ID>(n: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let mut points = vec![Vect::new(0., 0., 0.)];
+    points.extend(circle(n, 1.));
+    let mut edges: Vec<(usize, usize)> = (1..=n).map(|v| (0, v)).collect();
+    if n > 2 {
+        edges.extend((0..n).map(|i| (i + 1, (i + 1) % n + 1)));
+    }
+    build(points, edges)
+}
+
+// a w x h lattice, node (x, y) is y * w + x. Periodic lattices wrap around into a torus.
+pub fn grid_2d_graph<T, E, ID>(w: usize, h: usize, periodic: bool) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    grid_3d_graph(w, h, 1, periodic)
+}
+
+// a w x h x d lattice, node (x, y, z) is (z * h + y) * w + x.
+pub fn grid_3d_graph<T, E, ID>(w: usize, h: usize, d: usize, periodic: bool) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let index = |x: usize, y: usize, z: usize| (z * h + y) * w + x;
+    let mut points: Vec<Vect> = Vec::new();
+    let mut edges: HashSet<(usize, usize)> = HashSet::new();
+    let step = |from: usize, to: usize, edges: &mut HashSet<(usize, usize)>| {
+        if from != to {
+            edges.insert((from.min(to), from.max(to)));
+        }
+    };
+    for z in 0..d {
+        for y in 0..h {
+            for x in 0..w {
+                points.push(Vect::new(x as Scalar, y as Scalar, z as Scalar));
+                let here = index(x, y, z);
+                if x + 1 < w || (periodic && w > 2) {
+                    step(here, index((x + 1) % w, y, z), &mut edges);
+                }
+                if y + 1 < h || (periodic && h > 2) {
+                    step(here, index(x, (y + 1) % h, z), &mut edges);
+                }
+                if z + 1 < d || (periodic && d > 2) {
+                    step(here, index(x, y, (z + 1) % d), &mut edges);
+                }
+            }
+        }
+    }
+    let mut edges: Vec<(usize, usize)> = edges.into_iter().collect();
+    edges.sort_unstable();
+    build(points, edges)
+}
+
+// 2^dim nodes, joined when their indices differ in one bit. Bit k is drawn along the direction
+// at angle k*pi/dim so the projection shows every dimension.
+pub fn hypercube_graph<T, E, ID>(dim: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let n = 1usize << dim;
+    let axes: Vec<Vect> = (0..dim)
+        .map(|k| {
+            let a = PI * k as Scalar / dim as Scalar;
+            Vect::new(a.cos(), a.sin(), 0.)
+        })
+        .collect();
+    let points = (0..n)
+        .map(|v| {
+            (0..dim)
+                .filter(|k| v & (1 << k) != 0)
+                .fold(Vect::new(0., 0., 0.), |p, k| p + axes[k])
+        })
+        .collect();
+    let edges = (0..n).flat_map(|v| {
+        (0..dim)
+            .map(move |k| (v, v ^ (1 << k)))
+            .filter(|(v, u)| v < u)
+    });
+    build(points, edges)
+}
+
+// a complete k-ary tree of the given depth (a lone root has depth 0). Node i has children
+// k*i+1 ..= k*i+k; levels are drawn top down with the leaves one unit apart.
+pub fn k_ary_tree<T, E, ID>(k: usize, depth: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    if k == 0 {
+        return build(vec![Vect::new(0., 0., 0.)], Vec::new());
+    }
+    let leaves = k.pow(depth as u32) as Scalar;
+    let mut points: Vec<Vect> = Vec::new();
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    let mut level_start = 0;
+    for level in 0..=depth {
+        let width = k.pow(level as u32);
+        let spacing = leaves / width as Scalar;
+        for p in 0..width {
+            let v = level_start + p;
+            points.push(Vect::new(
+                (p as Scalar + 0.5) * spacing - leaves / 2.,
+                level as Scalar,
+                0.,
+            ));
+            if v > 0 {
+                edges.push(((v - 1) / k, v));
+            }
+        }
+        level_start += width;
+    }
+    build(points, edges)
+}
+
+pub fn binary_tree<T, E, ID>(depth: usize) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    k_ary_tree(2, depth)
+}
+
+// outer pentagon 0..5, inner pentagram 5..10.
+pub fn petersen_graph<T, E, ID>() -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let mut points = circle(5, 2.);
+    points.extend(circle(5, 1.));
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for i in 0..5 {
+        edges.push((i, (i + 1) % 5));
+        edges.push((i, i + 5));
+        edges.push((i + 5, (i + 2) % 5 + 5));
+    }
+    build(points, edges)
+}
+
+// cubic Hamiltonian graphs in LCF notation: a cycle of n nodes plus a chord from node i to
+// i + shifts[i % shifts.len()].
+pub fn lcf_graph<T, E, ID>(n: usize, shifts: &[i64]) -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    let mut add = |u: usize, v: usize| {
+        if u != v && seen.insert((u.min(v), u.max(v))) {
+            edges.push((u, v));
+        }
+    };
+    for v in 0..n {
+        add(v, (v + 1) % n);
+    }
+    if !shifts.is_empty() {
+        for v in 0..n {
+            let u = (v as i64 + shifts[v % shifts.len()]).rem_euclid(n as i64) as usize;
+            add(v, u);
+        }
+    }
+    build(circle(n, 1.), edges)
+}
+
+pub fn heawood_graph<T, E, ID>() -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    lcf_graph(14, &[5, -5])
+}
+
+pub fn mobius_kantor_graph<T, E, ID>() -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    lcf_graph(16, &[5, -5])
+}
+
+pub fn pappus_graph<T, E, ID>() -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    lcf_graph(18, &[5, 7, -7, 7, -7, -5])
+}
+
+pub fn desargues_graph<T, E, ID>() -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    lcf_graph(20, &[5, -5, 9, -9])
+}
+
+pub fn dodecahedral_graph<T, E, ID>() -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    lcf_graph(20, &[10, 7, 4, -4, -7, 10, -4, 7, -7, 4])
+}
+
+pub fn frucht_graph<T, E, ID>() -> Positioned<T, E, ID>
+where
+    T: Default,
+    E: Default,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    lcf_graph(12, &[-5, -2, -4, 2, 5, -2, 2, 5, -2, -5, 4, 2])
+}
@@ -1,7 +1,10 @@
+#[cfg(test)]
 mod geomtests;
+pub mod matrix;
 
 use rand::Rng;
-use std::ops::{Add, Mul, Sub};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
 
 pub type Scalar = f64;
 
@@ -13,14 +16,13 @@ pub struct Vect {
 }
 impl Vect {
     pub fn random(min: f64, max: f64, use_z: bool) -> Vect {
-        let mut rng = rand::thread_rng();
-
+        Vect::random_from(&mut rand::thread_rng(), min, max, use_z)
+    }
+    pub fn random_from<R: Rng>(rng: &mut R, min: f64, max: f64, use_z: bool) -> Vect {
+        let x = rng.gen_range(min..max);
+        let y = rng.gen_range(min..max);
         let z_val = if use_z { rng.gen_range(min..max) } else { 0.0 };
-        Self {
-            x: rng.gen_range(min..max),
-            y: rng.gen_range(min..max),
-            z: z_val,
-        }
+        Self { x, y, z: z_val }
     }
 }
 
@@ -55,11 +57,53 @@ impl Mul for Vect {
         Vect::new(u2 * v3 - u3 * v2, u3 * v1 - u1 * v3, u1 * v2 - u2 * v1)
     }
 }
+impl Mul<Scalar> for Vect {
+    type Output = Vect;
+    fn mul(self, rhs: Scalar) -> Vect {
+        self.scalar_mul(rhs)
+    }
+}
+impl Mul<Vect> for Scalar {
+    type Output = Vect;
+    fn mul(self, rhs: Vect) -> Vect {
+        rhs.scalar_mul(self)
+    }
+}
+impl Div<Scalar> for Vect {
+    type Output = Vect;
+    fn div(self, rhs: Scalar) -> Vect {
+        Vect::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+impl Neg for Vect {
+    type Output = Vect;
+    fn neg(self) -> Vect {
+        Vect::new(-self.x, -self.y, -self.z)
+    }
+}
+impl AddAssign for Vect {
+    fn add_assign(&mut self, rhs: Vect) {
+        *self = *self + rhs;
+    }
+}
+impl SubAssign for Vect {
+    fn sub_assign(&mut self, rhs: Vect) {
+        *self = *self - rhs;
+    }
+}
+impl Sum for Vect {
+    fn sum<I: Iterator<Item = Vect>>(iter: I) -> Vect {
+        iter.fold(Vect::zero(), |acc, v| acc + v)
+    }
+}
 
 impl Vect {
     pub fn new(x: Scalar, y: Scalar, z: Scalar) -> Self {
         Self { x, y, z }
     }
+    pub fn zero() -> Vect {
+        Vect::new(0., 0., 0.)
+    }
     pub fn i() -> Vect {
         Vect::new(1., 0., 0.)
     }
@@ -96,4 +140,231 @@ impl Vect {
     pub fn dot(&self, rhs: Vect) -> Scalar {
         self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
     }
+    // None for the zero vector (and anything too short to divide by), where
+    // as_unit_vector gives NaNs.
+    pub fn normalize(&self) -> Option<Vect> {
+        let m = self.magnitude();
+        if m > Scalar::MIN_POSITIVE && m.is_finite() {
+            Some(*self / m)
+        } else {
+            None
+        }
+    }
+    pub fn normalize_or_zero(&self) -> Vect {
+        self.normalize().unwrap_or_else(Vect::zero)
+    }
+    // self at t = 0, rhs at t = 1.
+    pub fn lerp(&self, rhs: &Vect, t: Scalar) -> Vect {
+        *self + (*rhs - *self) * t
+    }
+    // in radians between 0 and PI, 0 when either is the zero vector.
+    pub fn angle_between(&self, rhs: &Vect) -> Scalar {
+        let m = self.magnitude() * rhs.magnitude();
+        if m == 0.0 {
+            return 0.0;
+        }
+        (self.dot(*rhs) / m).clamp(-1.0, 1.0).acos()
+    }
+    // the part of self along rhs, zero when rhs is.
+    pub fn project_onto(&self, rhs: &Vect) -> Vect {
+        let length = rhs.dot(*rhs);
+        if length == 0.0 {
+            return Vect::zero();
+        }
+        *rhs * (self.dot(*rhs) / length)
+    }
+    // the part of self at right angles to rhs.
+    pub fn reject_from(&self, rhs: &Vect) -> Vect {
+        *self - self.project_onto(rhs)
+    }
+}
+
+// Segments, boxes and polygons. Intersection, containment and area work in the x-y plane
+// like the drawings do; distances use all three coordinates.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Segment {
+    pub start: Vect,
+    pub end: Vect,
+}
+
+// twice the signed area of the triangle a b c in the x-y plane, positive counter clockwise.
+fn cross_2d(a: &Vect, b: &Vect, c: &Vect) -> Scalar {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+impl Segment {
+    pub fn new(start: Vect, end: Vect) -> Self {
+        Self { start, end }
+    }
+    pub fn length(&self) -> Scalar {
+        self.start.euclid_distance(&self.end)
+    }
+    pub fn closest_point(&self, p: &Vect) -> Vect {
+        let d = self.end - self.start;
+        let length = d.dot(d);
+        if length == 0.0 {
+            return self.start;
+        }
+        let t = ((*p - self.start).dot(d) / length).clamp(0.0, 1.0);
+        self.start + d.scalar_mul(t)
+    }
+    pub fn distance_to(&self, p: &Vect) -> Scalar {
+        self.closest_point(p).euclid_distance(p)
+    }
+    // whether the segments cross at a point inside both; touching ends and overlapping
+    // collinear segments do not count.
+    pub fn crosses(&self, other: &Segment) -> bool {
+        let (a, b, c, d) = (&self.start, &self.end, &other.start, &other.end);
+        cross_2d(a, b, c) * cross_2d(a, b, d) < 0.0 && cross_2d(c, d, a) * cross_2d(c, d, b) < 0.0
+    }
+    // a point the segments share, including touching ends; for overlapping collinear
+    // segments the end of the overlap nearest this segment's start.
+    pub fn intersection(&self, other: &Segment) -> Option<Vect> {
+        let r = self.end - self.start;
+        let s = other.end - other.start;
+        let q = other.start - self.start;
+        let denominator = r.x * s.y - r.y * s.x;
+        let unit = 0.0..=1.0;
+        if denominator != 0.0 {
+            let t = (q.x * s.y - q.y * s.x) / denominator;
+            let u = (q.x * r.y - q.y * r.x) / denominator;
+            return (unit.contains(&t) && unit.contains(&u)).then(|| self.start + r.scalar_mul(t));
+        }
+        // parallel, only collinear segments can meet.
+        if cross_2d(&self.start, &self.end, &other.start) != 0.0
+            || cross_2d(&other.start, &other.end, &self.start) != 0.0
+        {
+            return None;
+        }
+        let (rr, ss) = (r.x * r.x + r.y * r.y, s.x * s.x + s.y * s.y);
+        if rr == 0.0 {
+            let u = if ss == 0.0 {
+                0.0
+            } else {
+                -(q.x * s.x + q.y * s.y) / ss
+            };
+            let same = ss != 0.0 || (q.x == 0.0 && q.y == 0.0);
+            return (same && unit.contains(&u)).then_some(self.start);
+        }
+        // positions of the other ends along this segment, 0 at start and 1 at end.
+        let t0 = (q.x * r.x + q.y * r.y) / rr;
+        let t1 = t0 + (s.x * r.x + s.y * r.y) / rr;
+        let (lo, hi) = (t0.min(t1).max(0.0), t0.max(t1).min(1.0));
+        (lo <= hi).then(|| self.start + r.scalar_mul(lo))
+    }
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::of_points([self.start, self.end].iter()).unwrap()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BoundingBox {
+    pub min: Vect,
+    pub max: Vect,
+}
+
+impl BoundingBox {
+    // None for no points.
+    pub fn of_points<'a, I: IntoIterator<Item = &'a Vect>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        Some(points.fold(Self::new(first, first), |b, p| b.including(p)))
+    }
+    pub fn new(min: Vect, max: Vect) -> Self {
+        Self { min, max }
+    }
+    pub fn including(&self, p: &Vect) -> BoundingBox {
+        BoundingBox::new(
+            Vect::new(
+                self.min.x.min(p.x),
+                self.min.y.min(p.y),
+                self.min.z.min(p.z),
+            ),
+            Vect::new(
+                self.max.x.max(p.x),
+                self.max.y.max(p.y),
+                self.max.z.max(p.z),
+            ),
+        )
+    }
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        self.including(&other.min).including(&other.max)
+    }
+    // grown by the margin on every side.
+    pub fn expand(&self, margin: Scalar) -> BoundingBox {
+        let m = Vect::new(margin, margin, margin);
+        BoundingBox::new(self.min - m, self.max + m)
+    }
+    pub fn width(&self) -> Scalar {
+        self.max.x - self.min.x
+    }
+    pub fn height(&self) -> Scalar {
+        self.max.y - self.min.y
+    }
+    pub fn depth(&self) -> Scalar {
+        self.max.z - self.min.z
+    }
+    pub fn centre(&self) -> Vect {
+        (self.min + self.max).scalar_mul(0.5)
+    }
+    pub fn contains(&self, p: &Vect) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+}
+
+// a closed polygon through the vertices in order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Polygon {
+    pub vertices: Vec<Vect>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Vect>) -> Self {
+        Self { vertices }
+    }
+    pub fn edges(&self) -> Vec<Segment> {
+        let n = self.vertices.len();
+        (0..n)
+            .map(|i| Segment::new(self.vertices[i], self.vertices[(i + 1) % n]))
+            .collect()
+    }
+    // shoelace formula, positive when the vertices run counter clockwise.
+    pub fn signed_area(&self) -> Scalar {
+        let origin = Vect::new(0., 0., 0.);
+        self.edges()
+            .iter()
+            .map(|e| cross_2d(&origin, &e.start, &e.end))
+            .sum::<Scalar>()
+            / 2.0
+    }
+    pub fn area(&self) -> Scalar {
+        self.signed_area().abs()
+    }
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::of_points(self.vertices.iter())
+    }
+    // even-odd rule, so points on the boundary may fall either way.
+    pub fn contains(&self, p: &Vect) -> bool {
+        let mut inside = false;
+        for e in self.edges() {
+            let (a, b) = (e.start, e.end);
+            if (a.y > p.y) != (b.y > p.y) {
+                let x = a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y);
+                if p.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
 }
@@ -1,4 +1,11 @@
-use crate::geometry::{Scalar, Vect};
+use crate::geometry::matrix::{transform_positions, Matrix3, Matrix4};
+use crate::geometry::{BoundingBox, Polygon, Scalar, Segment, Vect};
+use std::collections::HashMap;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+fn near(a: &Vect, b: &Vect) -> bool {
+    a.euclid_distance(b) < 1e-9
+}
 
 #[test]
 fn cross_product() {
@@ -31,3 +38,181 @@ fn distance() {
     let x = v1.euclid_distance(&v2);
     assert!((4.23..4.26).contains(&x));
 }
+
+#[test]
+fn segment_intersections() {
+    let a = Segment::new(Vect::new(0., 0., 0.), Vect::new(2., 2., 0.));
+    let b = Segment::new(Vect::new(0., 2., 0.), Vect::new(2., 0., 0.));
+    assert!(a.crosses(&b));
+    assert_eq!(Some(Vect::new(1., 1., 0.)), a.intersection(&b));
+
+    // touching at an end meets but does not cross.
+    let c = Segment::new(Vect::new(2., 2., 0.), Vect::new(3., 0., 0.));
+    assert!(!a.crosses(&c));
+    assert_eq!(Some(Vect::new(2., 2., 0.)), a.intersection(&c));
+
+    let parallel = Segment::new(Vect::new(0., 1., 0.), Vect::new(2., 3., 0.));
+    assert_eq!(None, a.intersection(&parallel));
+    let overlap = Segment::new(Vect::new(3., 3., 0.), Vect::new(1., 1., 0.));
+    assert!(!a.crosses(&overlap));
+    assert_eq!(Some(Vect::new(1., 1., 0.)), a.intersection(&overlap));
+    let beyond = Segment::new(Vect::new(3., 3., 0.), Vect::new(4., 4., 0.));
+    assert_eq!(None, a.intersection(&beyond));
+}
+
+#[test]
+fn segment_distances() {
+    let s = Segment::new(Vect::new(0., 0., 0.), Vect::new(4., 0., 0.));
+    assert_eq!(4.0, s.length());
+    assert_eq!(3.0, s.distance_to(&Vect::new(2., 3., 0.)));
+    assert_eq!(5.0, s.distance_to(&Vect::new(7., 4., 0.)));
+    assert_eq!(
+        Vect::new(0., 0., 0.),
+        s.closest_point(&Vect::new(-1., 1., 0.))
+    );
+    let point = Segment::new(Vect::new(1., 1., 1.), Vect::new(1., 1., 1.));
+    assert_eq!(1.0, point.distance_to(&Vect::new(1., 1., 2.)));
+}
+
+#[test]
+fn bounding_boxes() {
+    let points = [
+        Vect::new(1., -2., 0.),
+        Vect::new(-3., 4., 1.),
+        Vect::new(2., 0., -1.),
+    ];
+    let b = BoundingBox::of_points(points.iter()).unwrap();
+    assert_eq!(Vect::new(-3., -2., -1.), b.min);
+    assert_eq!(Vect::new(2., 4., 1.), b.max);
+    assert_eq!((5., 6., 2.), (b.width(), b.height(), b.depth()));
+    assert!(points.iter().all(|p| b.contains(p)));
+    assert!(!b.contains(&Vect::new(0., 5., 0.)));
+    assert!(BoundingBox::of_points([].iter()).is_none());
+
+    let far = BoundingBox::new(Vect::new(3., 0., 0.), Vect::new(4., 1., 0.));
+    assert!(!b.intersects(&far));
+    assert!(b.expand(1.0).intersects(&far));
+    assert_eq!(Vect::new(4., 4., 1.), b.union(&far).max);
+}
+
+#[test]
+fn polygon_containment() {
+    // a U shape, the notch is outside.
+    let u = Polygon::new(vec![
+        Vect::new(0., 0., 0.),
+        Vect::new(3., 0., 0.),
+        Vect::new(3., 3., 0.),
+        Vect::new(2., 3., 0.),
+        Vect::new(2., 1., 0.),
+        Vect::new(1., 1., 0.),
+        Vect::new(1., 3., 0.),
+        Vect::new(0., 3., 0.),
+    ]);
+    assert!(u.contains(&Vect::new(0.5, 2., 0.)));
+    assert!(u.contains(&Vect::new(1.5, 0.5, 0.)));
+    assert!(!u.contains(&Vect::new(1.5, 2., 0.)));
+    assert!(!u.contains(&Vect::new(4., 1., 0.)));
+    assert_eq!(7.0, u.area());
+    assert!(u.signed_area() > 0.0);
+    assert_eq!(8, u.edges().len());
+    assert_eq!(Vect::new(3., 3., 0.), u.bounding_box().unwrap().max);
+}
+
+#[test]
+fn vector_operators() {
+    let mut v = Vect::new(1., -2., 3.);
+    assert_eq!(Vect::new(-1., 2., -3.), -v);
+    assert_eq!(Vect::new(2., -4., 6.), v * 2.0);
+    assert_eq!(Vect::new(2., -4., 6.), 2.0 * v);
+    assert_eq!(Vect::new(0.5, -1., 1.5), v / 2.0);
+    v += Vect::new(1., 1., 1.);
+    assert_eq!(Vect::new(2., -1., 4.), v);
+    v -= Vect::new(2., -1., 4.);
+    assert_eq!(Vect::zero(), v);
+    let total: Vect = [Vect::i(), Vect::j(), Vect::k()].into_iter().sum();
+    assert_eq!(Vect::new(1., 1., 1.), total);
+}
+
+#[test]
+fn safe_normalize_and_projections() {
+    assert_eq!(None, Vect::zero().normalize());
+    assert_eq!(Vect::zero(), Vect::zero().normalize_or_zero());
+    assert_eq!(
+        Some(Vect::new(0.6, 0.8, 0.)),
+        Vect::new(3., 4., 0.).normalize()
+    );
+
+    let a = Vect::new(2., 0., 0.);
+    let b = Vect::new(4., 4., 0.);
+    assert_eq!(Vect::new(3., 2., 0.), a.lerp(&b, 0.5));
+    assert_eq!(b, a.lerp(&b, 1.0));
+    assert!((a.angle_between(&b) - PI / 4.0).abs() < 1e-12);
+    assert_eq!(0.0, a.angle_between(&Vect::zero()));
+    assert_eq!(Vect::new(4., 0., 0.), b.project_onto(&a));
+    assert_eq!(Vect::new(0., 4., 0.), b.reject_from(&a));
+    assert_eq!(Vect::zero(), b.project_onto(&Vect::zero()));
+}
+
+#[test]
+fn rotations_and_inverses() {
+    let r = Matrix3::rotation_z(FRAC_PI_2);
+    assert!(near(&Vect::j(), &(r * Vect::i())));
+    assert!(near(
+        &Vect::k(),
+        &(Matrix3::rotation_x(FRAC_PI_2) * Vect::j())
+    ));
+    assert!(near(
+        &Vect::i(),
+        &(Matrix3::rotation_y(FRAC_PI_2) * Vect::k())
+    ));
+
+    // about any axis agrees with the fixed axes, and rotating keeps lengths.
+    let tilted = Matrix3::rotation(&Vect::new(0., 0., 5.), 0.7);
+    let v = Vect::new(1., 2., 3.);
+    assert!(near(&(Matrix3::rotation_z(0.7) * v), &(tilted * v)));
+    assert!(((tilted * v).magnitude() - v.magnitude()).abs() < 1e-12);
+    assert!((tilted.determinant() - 1.0).abs() < 1e-12);
+    assert!(near(&v, &(tilted.transpose() * (tilted * v))));
+
+    let m = Matrix3::new([[2., 1., 0.], [0., 3., 1.], [1., 0., 4.]]);
+    let inverse = m.inverse().unwrap();
+    assert!(near(&v, &(inverse * (m * v))));
+    assert_eq!(None, Matrix3::scaling(&Vect::new(1., 0., 1.)).inverse());
+}
+
+#[test]
+fn affine_transforms() {
+    let centre = Vect::new(1., 1., 0.);
+    let spin = Matrix4::about(&centre, &Matrix3::rotation_z(PI));
+    assert!(near(
+        &Vect::new(2., 2., 0.),
+        &spin.transform_point(&Vect::zero())
+    ));
+    assert!(near(&centre, &spin.transform_point(&centre)));
+    assert!(near(&-Vect::i(), &spin.transform_direction(&Vect::i())));
+
+    let m = Matrix4::translation(&Vect::new(1., 2., 3.))
+        * Matrix4::rotation(&Vect::new(1., 1., 0.), 0.3)
+        * Matrix4::scaling(&Vect::new(2., 2., 2.));
+    let p = Vect::new(-4., 0.5, 2.);
+    assert!(near(
+        &p,
+        &m.inverse().unwrap().transform_point(&m.transform_point(&p))
+    ));
+    assert!(near(
+        &Vect::new(1., 2., 3.),
+        &m.transform_point(&Vect::zero())
+    ));
+
+    // a projective bottom row divides through.
+    let mut project = Matrix4::identity();
+    project.m[3] = [0., 0., 1., 0.];
+    assert!(near(
+        &Vect::new(0.5, 1., 1.),
+        &project.transform_point(&Vect::new(1., 2., 2.))
+    ));
+
+    let positions: HashMap<u32, Vect> = HashMap::from([(1, Vect::i()), (2, Vect::j())]);
+    let moved = transform_positions(&positions, &Matrix4::scaling(&Vect::new(3., 3., 3.)));
+    assert_eq!(Vect::new(0., 3., 0.), moved[&2]);
+}
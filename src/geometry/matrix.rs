@@ -0,0 +1,235 @@
+use crate::geometry::{Scalar, Vect};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Mul;
+
+// Row major 3x3 and 4x4 matrices acting on column vectors, so `a * b` applies b first.
+// Matrix3 covers rotation and scaling about the origin, Matrix4 adds translation for
+// affine transforms of whole layouts. Rotations are counter clockwise looking down the
+// axis towards the origin.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix3 {
+    pub m: [[Scalar; 3]; 3],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix4 {
+    pub m: [[Scalar; 4]; 4],
+}
+
+impl Matrix3 {
+    pub fn new(m: [[Scalar; 3]; 3]) -> Self {
+        Self { m }
+    }
+    pub fn identity() -> Self {
+        Self::scaling(&Vect::new(1., 1., 1.))
+    }
+    pub fn scaling(s: &Vect) -> Self {
+        Self::new([[s.x, 0., 0.], [0., s.y, 0.], [0., 0., s.z]])
+    }
+    pub fn rotation_x(angle: Scalar) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self::new([[1., 0., 0.], [0., c, -s], [0., s, c]])
+    }
+    pub fn rotation_y(angle: Scalar) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self::new([[c, 0., s], [0., 1., 0.], [-s, 0., c]])
+    }
+    pub fn rotation_z(angle: Scalar) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self::new([[c, -s, 0.], [s, c, 0.], [0., 0., 1.]])
+    }
+    // Rodrigues' formula, the identity for a zero axis.
+    pub fn rotation(axis: &Vect, angle: Scalar) -> Self {
+        let k = match axis.normalize() {
+            Some(k) => k,
+            None => return Self::identity(),
+        };
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        Self::new([
+            [
+                t * k.x * k.x + c,
+                t * k.x * k.y - s * k.z,
+                t * k.x * k.z + s * k.y,
+            ],
+            [
+                t * k.x * k.y + s * k.z,
+                t * k.y * k.y + c,
+                t * k.y * k.z - s * k.x,
+            ],
+            [
+                t * k.x * k.z - s * k.y,
+                t * k.y * k.z + s * k.x,
+                t * k.z * k.z + c,
+            ],
+        ])
+    }
+    pub fn transpose(&self) -> Self {
+        let mut t = [[0.0; 3]; 3];
+        for (r, row) in self.m.iter().enumerate() {
+            for (c, v) in row.iter().enumerate() {
+                t[c][r] = *v;
+            }
+        }
+        Self::new(t)
+    }
+    pub fn determinant(&self) -> Scalar {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+    // None when singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let m = &self.m;
+        // cofactor of (r, c), rows and columns taken cyclically so the signs work out.
+        let cofactor = |r: usize, c: usize| {
+            let (r1, r2) = ((r + 1) % 3, (r + 2) % 3);
+            let (c1, c2) = ((c + 1) % 3, (c + 2) % 3);
+            m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]
+        };
+        let mut inv = [[0.0; 3]; 3];
+        for (r, row) in inv.iter_mut().enumerate() {
+            for (c, v) in row.iter_mut().enumerate() {
+                *v = cofactor(c, r) / det;
+            }
+        }
+        Some(Self::new(inv))
+    }
+}
+
+impl Mul for Matrix3 {
+    type Output = Matrix3;
+    fn mul(self, rhs: Matrix3) -> Matrix3 {
+        let mut m = [[0.0; 3]; 3];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, v) in row.iter_mut().enumerate() {
+                *v = (0..3).map(|k| self.m[r][k] * rhs.m[k][c]).sum();
+            }
+        }
+        Matrix3::new(m)
+    }
+}
+
+impl Mul<Vect> for Matrix3 {
+    type Output = Vect;
+    fn mul(self, v: Vect) -> Vect {
+        let row = |r: [Scalar; 3]| r[0] * v.x + r[1] * v.y + r[2] * v.z;
+        Vect::new(row(self.m[0]), row(self.m[1]), row(self.m[2]))
+    }
+}
+
+impl Matrix4 {
+    pub fn new(m: [[Scalar; 4]; 4]) -> Self {
+        Self { m }
+    }
+    pub fn identity() -> Self {
+        Self::from_linear(&Matrix3::identity())
+    }
+    // the 3x3 part with no translation.
+    pub fn from_linear(l: &Matrix3) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (row, l_row) in m.iter_mut().zip(l.m.iter()) {
+            row[..3].copy_from_slice(l_row);
+        }
+        m[3][3] = 1.0;
+        Self::new(m)
+    }
+    pub fn translation(t: &Vect) -> Self {
+        let mut m = Self::identity();
+        m.m[0][3] = t.x;
+        m.m[1][3] = t.y;
+        m.m[2][3] = t.z;
+        m
+    }
+    pub fn scaling(s: &Vect) -> Self {
+        Self::from_linear(&Matrix3::scaling(s))
+    }
+    pub fn rotation(axis: &Vect, angle: Scalar) -> Self {
+        Self::from_linear(&Matrix3::rotation(axis, angle))
+    }
+    // applies l about `centre` instead of the origin.
+    pub fn about(centre: &Vect, l: &Matrix3) -> Self {
+        Self::translation(centre) * Self::from_linear(l) * Self::translation(&-*centre)
+    }
+    pub fn linear(&self) -> Matrix3 {
+        let mut l = [[0.0; 3]; 3];
+        for (r, row) in l.iter_mut().enumerate() {
+            row.copy_from_slice(&self.m[r][..3]);
+        }
+        Matrix3::new(l)
+    }
+    // with the perspective divide when the bottom row is not 0 0 0 1.
+    pub fn transform_point(&self, p: &Vect) -> Vect {
+        let row = |r: [Scalar; 4]| r[0] * p.x + r[1] * p.y + r[2] * p.z + r[3];
+        let w = row(self.m[3]);
+        let v = Vect::new(row(self.m[0]), row(self.m[1]), row(self.m[2]));
+        if w != 0.0 && w != 1.0 {
+            v / w
+        } else {
+            v
+        }
+    }
+    // ignores the translation.
+    pub fn transform_direction(&self, d: &Vect) -> Vect {
+        self.linear() * *d
+    }
+    // None when singular, by Gauss-Jordan elimination with partial pivoting.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Self::identity().m;
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+            let p = a[col][col];
+            for c in 0..4 {
+                a[col][c] /= p;
+                inv[col][c] /= p;
+            }
+            for r in 0..4 {
+                if r != col {
+                    let f = a[r][col];
+                    let (top, top_inv) = (a[col], inv[col]);
+                    for c in 0..4 {
+                        a[r][c] -= f * top[c];
+                        inv[r][c] -= f * top_inv[c];
+                    }
+                }
+            }
+        }
+        Some(Self::new(inv))
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Matrix4;
+    fn mul(self, rhs: Matrix4) -> Matrix4 {
+        let mut m = [[0.0; 4]; 4];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, v) in row.iter_mut().enumerate() {
+                *v = (0..4).map(|k| self.m[r][k] * rhs.m[k][c]).sum();
+            }
+        }
+        Matrix4::new(m)
+    }
+}
+
+pub fn transform_positions<ID: Copy + Hash + Eq>(
+    positions: &HashMap<ID, Vect>,
+    m: &Matrix4,
+) -> HashMap<ID, Vect> {
+    positions
+        .iter()
+        .map(|(id, p)| (*id, m.transform_point(p)))
+        .collect()
+}
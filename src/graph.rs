@@ -50,7 +50,7 @@ pub struct GraphError {
 }
 
 impl GraphError {
-    fn new(m: &str) -> Self {
+    pub(crate) fn new(m: &str) -> Self {
         Self {
             message: m.to_string(),
         }
@@ -259,6 +259,28 @@ impl<T, E, ID: Copy + Clone + Hash + Eq> Graph<T, E, ID> {
             }
         };
     }
+    // edges touching the node in the order they were added, a self loop once.
+    pub fn incident_edges(&self, id: ID) -> Result<Vec<&Edge<E, ID>>, GraphError> {
+        match self.nodes.get(&id) {
+            None => Err(GraphError::new("'Supplied node id is not in graph.")),
+            Some(node) => {
+                let mut edges: Vec<&Edge<E, ID>> = Vec::new();
+                for edge_id in node.edges.iter() {
+                    if !edges.iter().any(|e| e.id == *edge_id) {
+                        edges.push(&self.edges[edge_id]);
+                    }
+                }
+                Ok(edges)
+            }
+        }
+    }
+    // number of edge ends at the node, so a self loop counts twice.
+    pub fn degree(&self, id: ID) -> Result<usize, GraphError> {
+        match self.nodes.get(&id) {
+            None => Err(GraphError::new("'Supplied node id is not in graph.")),
+            Some(node) => Ok(node.edges.len()),
+        }
+    }
 }
 
 #[cfg(test)]
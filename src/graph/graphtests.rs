@@ -60,6 +60,30 @@ fn bfs_path() {
     }
 }
 
+#[test]
+fn incident_edges_and_degree() {
+    let mut g: Graph<usize, usize, char> = Graph::new();
+    for x in ['A', 'B', 'C'] {
+        g.add_node(x, 0);
+    }
+    g.add_edge('a', 'A', 'B', 1).unwrap();
+    g.add_edge('b', 'C', 'A', 2).unwrap();
+    g.add_edge('c', 'A', 'A', 3).unwrap();
+
+    let ids: Vec<char> = g
+        .incident_edges('A')
+        .unwrap()
+        .iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(ids, vec!['a', 'b', 'c']);
+    assert_eq!(g.incident_edges('B').unwrap()[0].data, 1);
+    assert_eq!(g.degree('A').unwrap(), 4);
+    assert_eq!(g.degree('C').unwrap(), 1);
+    assert!(g.degree('Z').is_err());
+    assert!(g.incident_edges('Z').is_err());
+}
+
 #[test]
 fn apply_nodes() {
     let mut g1: Graph<i32, i32, i32> = create_random_graph::<i32, i32, i32>(10, 20, 1, 10, 0, 1);
@@ -0,0 +1,326 @@
+use crate::geometry::Scalar;
+use rand::Rng;
+
+// Sparse symmetric eigenproblems for the spectral code. Matrices are stored as compressed
+// rows; the solvers only need matrix vector products.
+const BREAKDOWN: Scalar = 1e-10;
+const QL_MAX_SWEEPS: usize = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+    pub size: usize,
+    row_start: Vec<usize>,
+    cols: Vec<usize>,
+    values: Vec<Scalar>,
+}
+
+impl SparseMatrix {
+    // entries at the same position are summed.
+    pub fn from_triplets(size: usize, entries: Vec<(usize, usize, Scalar)>) -> Self {
+        let mut entries = entries;
+        entries.sort_by_key(|e| (e.0, e.1));
+        let mut row_start = vec![0; size + 1];
+        let mut cols: Vec<usize> = Vec::new();
+        let mut values: Vec<Scalar> = Vec::new();
+        let mut last: Option<(usize, usize)> = None;
+        for (r, c, v) in entries {
+            if last == Some((r, c)) {
+                *values.last_mut().unwrap() += v;
+                continue;
+            }
+            last = Some((r, c));
+            row_start[r + 1] += 1;
+            cols.push(c);
+            values.push(v);
+        }
+        for r in 0..size {
+            row_start[r + 1] += row_start[r];
+        }
+        Self {
+            size,
+            row_start,
+            cols,
+            values,
+        }
+    }
+    pub fn get(&self, r: usize, c: usize) -> Scalar {
+        let row = self.row_start[r]..self.row_start[r + 1];
+        match self.cols[row.clone()].binary_search(&c) {
+            Ok(ix) => self.values[row.start + ix],
+            Err(_) => 0.0,
+        }
+    }
+    pub fn mul(&self, x: &[Scalar]) -> Vec<Scalar> {
+        (0..self.size)
+            .map(|r| {
+                (self.row_start[r]..self.row_start[r + 1])
+                    .map(|ix| self.values[ix] * x[self.cols[ix]])
+                    .sum()
+            })
+            .collect()
+    }
+    pub fn to_dense(&self) -> Vec<Vec<Scalar>> {
+        (0..self.size)
+            .map(|r| (0..self.size).map(|c| self.get(r, c)).collect())
+            .collect()
+    }
+}
+
+pub fn dot(a: &[Scalar], b: &[Scalar]) -> Scalar {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+pub fn norm(a: &[Scalar]) -> Scalar {
+    dot(a, a).sqrt()
+}
+
+// removes the components along the (orthonormal) basis, twice for stability.
+fn orthogonalize(v: &mut [Scalar], basis: &[Vec<Scalar>]) {
+    for _ in 0..2 {
+        for b in basis {
+            let p = dot(v, b);
+            for (x, y) in v.iter_mut().zip(b.iter()) {
+                *x -= p * y;
+            }
+        }
+    }
+}
+
+fn random_unit<R: Rng>(rng: &mut R, size: usize, against: &[Vec<Scalar>]) -> Option<Vec<Scalar>> {
+    for _ in 0..10 {
+        let mut v: Vec<Scalar> = (0..size).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        orthogonalize(&mut v, against);
+        let n = norm(&v);
+        if n > BREAKDOWN {
+            return Some(v.into_iter().map(|x| x / n).collect());
+        }
+    }
+    None
+}
+
+// Eigenvalues (ascending) and eigenvectors of the symmetric tridiagonal matrix with the
+// given diagonal and off diagonal, by implicit QL with Wilkinson shifts.
+pub fn tridiagonal_eigen(diagonal: &[Scalar], off: &[Scalar]) -> (Vec<Scalar>, Vec<Vec<Scalar>>) {
+    let n = diagonal.len();
+    // accumulated rotations, the eigenvectors end up in its columns.
+    let mut z: Vec<Vec<Scalar>> = (0..n)
+        .map(|k| (0..n).map(|i| if i == k { 1.0 } else { 0.0 }).collect())
+        .collect();
+    let d = implicit_ql(diagonal, off, &mut z);
+    let vectors: Vec<Vec<Scalar>> = (0..n)
+        .map(|k| z.iter().map(|row| row[k]).collect())
+        .collect();
+    let mut pairs: Vec<(Scalar, Vec<Scalar>)> = d.into_iter().zip(vectors).collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+    pairs.into_iter().unzip()
+}
+
+// The eigenvalues (ascending) with only the last entry of each eigenvector, which is all a
+// Lanczos convergence check needs, in O(n^2) rather than O(n^3).
+fn tridiagonal_last_entries(diagonal: &[Scalar], off: &[Scalar]) -> Vec<(Scalar, Scalar)> {
+    let n = diagonal.len();
+    let mut z = vec![(0..n).map(|i| if i + 1 == n { 1.0 } else { 0.0 }).collect()];
+    let d = implicit_ql(diagonal, off, &mut z);
+    let mut pairs: Vec<(Scalar, Scalar)> = d.into_iter().zip(z[0].iter().copied()).collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+    pairs
+}
+
+// Implicit QL with Wilkinson shifts, returning the eigenvalues unsorted and applying the
+// rotations to the rows of `z`.
+fn implicit_ql(diagonal: &[Scalar], off: &[Scalar], z: &mut [Vec<Scalar>]) -> Vec<Scalar> {
+    let n = diagonal.len();
+    let mut d = diagonal.to_vec();
+    let mut e: Vec<Scalar> = off.to_vec();
+    e.resize(n, 0.0);
+
+    for l in 0..n {
+        for _ in 0..QL_MAX_SWEEPS {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m + 1].abs();
+                if e[m].abs() <= Scalar::EPSILON * dd {
+                    break;
+                }
+                m += 1;
+            }
+            if m == l {
+                break;
+            }
+            let mut g = (d[l + 1] - d[l]) / (2.0 * e[l]);
+            let mut r = g.hypot(1.0);
+            g = d[m] - d[l] + e[l] / (g + r.copysign(g));
+            let (mut s, mut c, mut p) = (1.0, 1.0, 0.0);
+            let mut i = m;
+            let mut underflow = false;
+            while i > l {
+                i -= 1;
+                let f = s * e[i];
+                let b = c * e[i];
+                r = f.hypot(g);
+                e[i + 1] = r;
+                if r == 0.0 {
+                    d[i + 1] -= p;
+                    e[m] = 0.0;
+                    underflow = true;
+                    break;
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i + 1] - p;
+                r = (d[i] - g) * s + 2.0 * c * b;
+                p = s * r;
+                d[i + 1] = g + p;
+                g = c * r - b;
+                for zk in z.iter_mut() {
+                    let t = zk[i + 1];
+                    zk[i + 1] = s * zk[i] + c * t;
+                    zk[i] = c * zk[i] - s * t;
+                }
+            }
+            if underflow {
+                continue;
+            }
+            d[l] -= p;
+            e[l] = g;
+            e[m] = 0.0;
+        }
+    }
+    d
+}
+
+// Lanczos with full reorthogonalisation for `steps` steps (at most the matrix size),
+// returning the basis and the tridiagonal's diagonal and off diagonal. `done` sees those and
+// the norm of the next vector before each extension and can stop early. A breakdown
+// restarts from a fresh vector orthogonal to the basis.
+fn krylov_basis<R: Rng>(
+    m: &SparseMatrix,
+    steps: usize,
+    rng: &mut R,
+    mut done: impl FnMut(&[Scalar], &[Scalar], Scalar) -> bool,
+) -> (Vec<Vec<Scalar>>, Vec<Scalar>, Vec<Scalar>) {
+    let steps = steps.min(m.size);
+    let mut basis: Vec<Vec<Scalar>> = Vec::with_capacity(steps);
+    let mut alpha: Vec<Scalar> = Vec::with_capacity(steps);
+    let mut beta: Vec<Scalar> = Vec::with_capacity(steps);
+    let mut q = match random_unit(rng, m.size, &[]) {
+        Some(q) => q,
+        None => return (basis, alpha, beta),
+    };
+    for j in 0..steps {
+        let mut w = m.mul(&q);
+        let a = dot(&w, &q);
+        alpha.push(a);
+        basis.push(q);
+        if j + 1 == steps {
+            break;
+        }
+        orthogonalize(&mut w, &basis);
+        let b = norm(&w);
+        if done(&alpha, &beta, b) {
+            break;
+        }
+        if b > BREAKDOWN {
+            beta.push(b);
+            q = w.into_iter().map(|x| x / b).collect();
+        } else {
+            beta.push(0.0);
+            q = match random_unit(rng, m.size, &basis) {
+                Some(q) => q,
+                None => break,
+            };
+        }
+    }
+    beta.truncate(alpha.len().saturating_sub(1));
+    (basis, alpha, beta)
+}
+
+fn ritz_vector(y: &[Scalar], basis: &[Vec<Scalar>], size: usize) -> Vec<Scalar> {
+    let mut v = vec![0.0; size];
+    for (coef, b) in y.iter().zip(basis.iter()) {
+        for (x, bx) in v.iter_mut().zip(b.iter()) {
+            *x += coef * bx;
+        }
+    }
+    v
+}
+
+// Lanczos for `steps` steps (at most the matrix size), returning the Ritz pairs in ascending
+// order. With steps equal to the size the result is the full spectrum.
+pub fn lanczos<R: Rng>(
+    m: &SparseMatrix,
+    steps: usize,
+    rng: &mut R,
+) -> (Vec<Scalar>, Vec<Vec<Scalar>>) {
+    let (basis, alpha, beta) = krylov_basis(m, steps, rng, |_, _, _| false);
+    let (values, small) = tridiagonal_eigen(&alpha, &beta);
+    let vectors = small
+        .iter()
+        .map(|y| ritz_vector(y, &basis, m.size))
+        .collect();
+    (values, vectors)
+}
+
+// The `count` smallest eigenpairs, ascending. The basis grows until each of them has a
+// residual |Mv - λv| below `tolerance`, checked after `first_check` steps and whenever the
+// basis has doubled since, or until it spans the whole space. The residual of a Ritz pair
+// is the next off diagonal times the last entry of its tridiagonal eigenvector.
+pub fn lanczos_smallest<R: Rng>(
+    m: &SparseMatrix,
+    count: usize,
+    first_check: usize,
+    tolerance: Scalar,
+    rng: &mut R,
+) -> (Vec<Scalar>, Vec<Vec<Scalar>>) {
+    let mut check = first_check.max(1);
+    let (basis, alpha, beta) = krylov_basis(m, m.size, rng, |alpha, beta, next| {
+        if alpha.len() < check {
+            return false;
+        }
+        check *= 2;
+        tridiagonal_last_entries(alpha, beta)
+            .iter()
+            .take(count)
+            .all(|(_, last)| (next * last).abs() < tolerance)
+    });
+    let (mut values, small) = tridiagonal_eigen(&alpha, &beta);
+    values.truncate(count);
+    let vectors = small
+        .iter()
+        .take(count)
+        .map(|y| ritz_vector(y, &basis, m.size))
+        .collect();
+    (values, vectors)
+}
+
+// Dominant eigenpair of the matrix restricted to the complement of `against` (orthonormal).
+pub fn power_iteration<R: Rng>(
+    m: &SparseMatrix,
+    against: &[Vec<Scalar>],
+    iterations: usize,
+    tolerance: Scalar,
+    rng: &mut R,
+) -> Option<(Scalar, Vec<Scalar>)> {
+    let mut v = random_unit(rng, m.size, against)?;
+    let mut value = 0.0;
+    for _ in 0..iterations {
+        let mut w = m.mul(&v);
+        orthogonalize(&mut w, against);
+        let n = norm(&w);
+        if n < BREAKDOWN {
+            return Some((0.0, v));
+        }
+        let next: Vec<Scalar> = w.into_iter().map(|x| x / n).collect();
+        value = dot(&m.mul(&next), &next);
+        // distance between the unit vectors, up to the sign a negative eigenvalue flips.
+        let change = (2.0 - 2.0 * dot(&next, &v).abs()).max(0.0).sqrt();
+        v = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    Some((value, v))
+}
+
+#[cfg(test)]
+mod linalgtests;
@@ -0,0 +1,94 @@
+use crate::generators::seeded_rng;
+use crate::geometry::Scalar;
+use crate::linalg::{
+    dot, lanczos, lanczos_smallest, norm, power_iteration, tridiagonal_eigen, SparseMatrix,
+};
+
+fn close(a: Scalar, b: Scalar) -> bool {
+    (a - b).abs() < 1e-8
+}
+
+#[test]
+fn sparse_matrix_sums_duplicates() {
+    let m =
+        SparseMatrix::from_triplets(3, vec![(2, 0, 1.0), (0, 1, 2.0), (2, 0, 3.0), (1, 1, 5.0)]);
+    assert_eq!(4.0, m.get(2, 0));
+    assert_eq!(0.0, m.get(0, 2));
+    assert_eq!(vec![2.0, 5.0, 4.0], m.mul(&[1.0, 1.0, 1.0]));
+    assert_eq!(vec![0.0, 2.0, 0.0], m.to_dense()[0]);
+}
+
+#[test]
+fn tridiagonal_eigenpairs() {
+    // 2 on the diagonal and -1 beside it: eigenvalues 2 - 2cos(k pi / (n + 1)).
+    let n = 6;
+    let (values, vectors) = tridiagonal_eigen(&[2.0; 6], &[-1.0; 5]);
+    for (k, v) in values.iter().enumerate() {
+        let expected =
+            2.0 - 2.0 * (((k + 1) as Scalar) * std::f64::consts::PI / (n + 1) as Scalar).cos();
+        assert!(close(expected, *v));
+    }
+    for (value, v) in values.iter().zip(vectors.iter()) {
+        assert!(close(1.0, norm(v)));
+        for i in 0..n {
+            let left = if i > 0 { v[i - 1] } else { 0.0 };
+            let right = if i + 1 < n { v[i + 1] } else { 0.0 };
+            assert!(close(value * v[i], 2.0 * v[i] - left - right));
+        }
+    }
+}
+
+#[test]
+fn lanczos_finds_repeated_eigenvalues() {
+    // all ones matrix of size 5: eigenvalue 5 once and 0 four times.
+    let entries = (0..5)
+        .flat_map(|r| (0..5).map(move |c| (r, c, 1.0)))
+        .collect();
+    let m = SparseMatrix::from_triplets(5, entries);
+    let (values, vectors) = lanczos(&m, 5, &mut seeded_rng(1));
+    assert_eq!(5, values.len());
+    for v in values.iter().take(4) {
+        assert!(v.abs() < 1e-8);
+    }
+    assert!(close(5.0, values[4]));
+    for (a, b) in vectors.iter().zip(vectors.iter().skip(1)) {
+        assert!(dot(a, b).abs() < 1e-8);
+    }
+}
+
+#[test]
+fn lanczos_smallest_pairs_have_small_residuals() {
+    // a ring of 400 with a heavy diagonal, its smallest eigenvalues are 2 - 2cos(2πk/400) + 4.
+    let entries = (0..400)
+        .flat_map(|r| {
+            vec![
+                (r, r, 6.0),
+                (r, (r + 1) % 400, -1.0),
+                ((r + 1) % 400, r, -1.0),
+            ]
+        })
+        .collect();
+    let m = SparseMatrix::from_triplets(400, entries);
+    let (values, vectors) = lanczos_smallest(&m, 1, 20, 1e-9, &mut seeded_rng(1));
+    assert_eq!(1, values.len());
+    assert!(close(4.0, values[0]));
+    let residual: Vec<Scalar> = m
+        .mul(&vectors[0])
+        .iter()
+        .zip(vectors[0].iter())
+        .map(|(mv, x)| mv - values[0] * x)
+        .collect();
+    assert!(norm(&residual) < 1e-9);
+}
+
+#[test]
+fn power_iteration_dominant_pair() {
+    let m =
+        SparseMatrix::from_triplets(2, vec![(0, 0, 2.0), (0, 1, 1.0), (1, 0, 1.0), (1, 1, 2.0)]);
+    let (value, v) = power_iteration(&m, &[], 500, 1e-12, &mut seeded_rng(2)).unwrap();
+    assert!(close(3.0, value));
+    assert!(close(v[0].abs(), v[1].abs()));
+    let s = (0.5 as Scalar).sqrt();
+    let (value, _) = power_iteration(&m, &[vec![s, s]], 500, 1e-12, &mut seeded_rng(2)).unwrap();
+    assert!(close(1.0, value));
+}
@@ -1,84 +1,549 @@
 #![allow(dead_code)]
 
-use crate::geometry::{Scalar, Vect};
+use crate::algorithms::packing::component_layout;
+use crate::algorithms::LayoutConfig;
+use crate::camera::{FlatView, OrbitCamera, DEFAULT_FOV};
+use crate::geometry::{BoundingBox, Scalar, Vect};
 use crate::graph::Graph;
+use crate::spatial::KdTree;
+use crate::style::{label_point, EdgeStyle, NodeStyle, Style};
 use crate::utils::create_random_graph;
 use crate::Shape::LineSegment;
 use eframe::egui::epaint::CircleShape;
-use eframe::egui::plot::{Line, Plot};
-use eframe::egui::{epaint, CentralPanel, Color32, CtxRef, Pos2, Shape, Stroke, Widget};
+use eframe::egui::{
+    show_tooltip_at_pointer, vec2, Align2, CentralPanel, Color32, CtxRef, Event, Key, Painter,
+    Pos2, Rect, Response, ScrollArea, Sense, Shape, SidePanel, Stroke, TextStyle, Ui,
+};
 use eframe::epi::{App, Frame};
 use eframe::{run_native, NativeOptions};
-use std::collections::HashMap;
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Debug;
 use std::hash::Hash;
 
 mod algorithms;
+mod camera;
+mod generators;
 mod geometry;
 mod graph;
+mod linalg;
+mod spatial;
+mod style;
 mod utils;
-const SCALE: f64 = 0.015;
-const OFFSET_X: f64 = 400.0;
-const OFFSET_Y: f64 = 400.0;
-const OFFSET_VEC: Vect = Vect {
-    x: OFFSET_X,
-    y: OFFSET_Y,
-    z: 0.0,
+
+// radians per dragged point and zoom per scrolled point.
+const ORBIT_SPEED: Scalar = 0.01;
+const ZOOM_SPEED: Scalar = 0.002;
+// what one press of the arrow keys and of + or - does.
+const PAN_STEP: Scalar = 40.0;
+const ORBIT_STEP: Scalar = 0.1;
+const KEY_ZOOM: Scalar = 1.25;
+// pixels left around the layout by fit-to-view.
+const FIT_MARGIN: Scalar = 20.0;
+// pixels between a node and its label.
+const LABEL_GAP: f32 = 2.0;
+// pixels from a node the pointer may be and still hit it, more for nodes drawn larger.
+const PICK_RADIUS: Scalar = 8.0;
+const SELECTED_COLOUR: Color32 = Color32::LIGHT_BLUE;
+const HOVER_STROKE: Stroke = Stroke {
+    width: 1.5,
+    color: Color32::GOLD,
 };
+// gap between the packed components of the layout.
+const COMPONENT_SPACING: Scalar = 50.0;
 struct GraphDisplay<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq> {
     pub g: Graph<T, E, ID>,
     pub points_to_display: HashMap<ID, Vect>,
+    // edge id to the bend points of its route, edges without any are straight.
+    pub bends: HashMap<ID, Vec<Vect>>,
+    // draws the points in 3D through this camera when set, flat otherwise.
+    pub camera: Option<OrbitCamera>,
+    pub style: Style<T, E, ID>,
+    pub view: FlatView,
+    // fit the view to the points once the size of the window is known.
+    pub fit_pending: bool,
+    pub selected: BTreeSet<ID>,
+    pub hovered: Option<ID>,
+    grab: Option<Grab>,
+    // of points_to_display for picking, dropped whenever a node moves.
+    index: Option<KdTree<ID>>,
+}
+
+// What a drag started in the flat view does, decided where the button went down: on a node
+// it moves the selection, on empty space it pans, or with shift held spans a box that adds
+// the nodes inside it to the selection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Grab {
+    Pan,
+    // `selected` when the press selected the node, the click that follows then leaves the
+    // selection alone.
+    Nodes { selected: bool },
+    Box(Pos2, Pos2),
+}
+
+// Mouse: drag pans (orbits in 3D) and the wheel or a pinch zooms at the pointer. A click
+// selects a node, with shift it toggles the node instead, and a click on nothing clears.
+// Keys: F or Home fits the view to the graph, the arrows pan (orbit), + and - or
+// PageUp and PageDown zoom, and R turns the 3D view back to face on.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Shortcuts {
+    fit: bool,
+    reset: bool,
+    // in steps, right and down positive.
+    pan: (Scalar, Scalar),
+    // factor, 1 for none.
+    zoom: Scalar,
 }
 
-impl<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq> GraphDisplay<T, E, ID> {
-    fn connections(&self, points: &HashMap<ID, Vect>) -> Vec<(Vect, Vect)> {
-        algorithms::connections(&points, &self.g)
+impl Shortcuts {
+    fn read(ui: &Ui) -> Self {
+        let input = ui.input();
+        let pressed = |key: Key| input.key_pressed(key);
+        let typed = |text: &str| {
+            input
+                .events
+                .iter()
+                .any(|e| matches!(e, Event::Text(t) if t == text))
+        };
+        let axis = |less: Key, more: Key| match (pressed(less), pressed(more)) {
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            _ => 0.0,
+        };
+        let mut zoom = 1.0;
+        if pressed(Key::PageUp) || typed("+") || typed("=") {
+            zoom *= KEY_ZOOM;
+        }
+        if pressed(Key::PageDown) || typed("-") {
+            zoom /= KEY_ZOOM;
+        }
+        Self {
+            fit: pressed(Key::F) || pressed(Key::Home),
+            reset: pressed(Key::R),
+            pan: (
+                axis(Key::ArrowLeft, Key::ArrowRight),
+                axis(Key::ArrowUp, Key::ArrowDown),
+            ),
+            zoom,
+        }
     }
-    fn convert_to_line(&self, (s1, e1): &(Vect, Vect)) -> Shape {
-        let s = s1.scalar_mul(SCALE) + OFFSET_VEC;
-        let e = e1.scalar_mul(SCALE) + OFFSET_VEC;
+}
 
+fn pos(x: Scalar, y: Scalar) -> Pos2 {
+    Pos2::new(x as f32, y as f32)
+}
+
+// the colour darkened by the factor, as far things are in 3D.
+fn dim(c: Color32, factor: f32) -> Color32 {
+    let scale = |v: u8| (v as f32 * factor.clamp(0.0, 1.0)) as u8;
+    Color32::from_rgba_premultiplied(scale(c.r()), scale(c.g()), scale(c.b()), c.a())
+}
+
+impl<T: Debug, E: Debug, ID: Debug + Copy + Ord + Clone + Hash + Eq> GraphDisplay<T, E, ID> {
+    fn new(
+        g: Graph<T, E, ID>,
+        points_to_display: HashMap<ID, Vect>,
+        camera: Option<OrbitCamera>,
+        style: Style<T, E, ID>,
+    ) -> Self {
+        Self {
+            g,
+            points_to_display,
+            bends: HashMap::new(),
+            camera,
+            style,
+            view: FlatView::default(),
+            fit_pending: true,
+            selected: BTreeSet::new(),
+            hovered: None,
+            grab: None,
+            index: None,
+        }
+    }
+    // every edge with its style and the points its route passes through.
+    fn routes(&self) -> Vec<(ID, EdgeStyle, Vec<Vect>)> {
+        algorithms::routed_edges(&self.points_to_display, &self.bends, &self.g)
+            .into_iter()
+            .map(|(id, route)| (id, self.style.edge(&self.g.edges[&id]), route))
+            .collect()
+    }
+    fn node_style(&self, id: &ID) -> NodeStyle {
+        self.style.node(&self.g.nodes[id])
+    }
+    fn convert_to_line(&self, (s, e): &(Vect, Vect), style: &EdgeStyle) -> Shape {
+        let (sx, sy) = self.view.screen_point(s);
+        let (ex, ey) = self.view.screen_point(e);
         LineSegment {
-            points: [
-                Pos2::new(s.x as f32, s.y as f32),
-                Pos2::new(e.x as f32, e.y as f32),
-            ],
+            points: [pos(sx, sy), pos(ex, ey)],
             stroke: Stroke {
-                width: 0.1,
-                color: Color32::WHITE,
+                width: style.width,
+                color: style.colour,
             },
         }
     }
-    fn convert_vect_to_circle(v1: &Vect) -> Shape {
-        let v = v1.scalar_mul(SCALE) + OFFSET_VEC;
-        let c = Shape::Circle(CircleShape {
-            center: Pos2::new(v.x as f32, v.y as f32),
-            radius: 5.0,
-            fill: Color32::WHITE,
-            stroke: Default::default(),
+    fn convert_vect_to_circle(&self, id: &ID, v: &Vect) -> Shape {
+        let (x, y) = self.view.screen_point(v);
+        self.node_circle(id, pos(x, y), self.node_style(id))
+    }
+    // as styled, except that selected nodes are filled and hovered ones outlined.
+    fn node_circle(&self, id: &ID, center: Pos2, style: NodeStyle) -> Shape {
+        Shape::Circle(CircleShape {
+            center,
+            radius: style.radius,
+            fill: if self.selected.contains(id) {
+                SELECTED_COLOUR
+            } else {
+                style.fill
+            },
+            stroke: if self.hovered == Some(*id) {
+                HOVER_STROKE
+            } else {
+                style.stroke
+            },
+        })
+    }
+    // node labels just above their nodes and edge labels halfway along the edges, given
+    // the screen position of a point and the radius nodes are drawn there with.
+    fn paint_labels(
+        &self,
+        painter: &Painter,
+        routes: &[(ID, EdgeStyle, Vec<Vect>)],
+        place: impl Fn(&Vect, f32) -> Option<(Pos2, f32)>,
+    ) {
+        let colour = self.style.label_colour;
+        for (id, v) in self.points_to_display.iter() {
+            let label = self.g.nodes.get(id).and_then(|n| self.style.node_label(n));
+            let at = place(v, self.node_style(id).radius);
+            if let (Some(label), Some((at, radius))) = (label, at) {
+                let above = at - vec2(0.0, radius + LABEL_GAP);
+                painter.text(
+                    above,
+                    Align2::CENTER_BOTTOM,
+                    label,
+                    TextStyle::Small,
+                    colour,
+                );
+            }
+        }
+        for (id, _, route) in routes {
+            let label = self.style.edge_label(&self.g.edges[id]);
+            let at = label_point(route).and_then(|p| place(&p, 0.0));
+            if let (Some(label), Some((at, _))) = (label, at) {
+                painter.text(at, Align2::CENTER_CENTER, label, TextStyle::Small, colour);
+            }
+        }
+    }
+    // a click on a node selects only it, or toggles it with shift; one on nothing clears.
+    fn click(&mut self, hit: Option<ID>, shift: bool) {
+        match hit {
+            Some(id) if shift => {
+                if !self.selected.remove(&id) {
+                    self.selected.insert(id);
+                }
+            }
+            Some(id) => self.selected = BTreeSet::from([id]),
+            None if shift => {}
+            None => self.selected.clear(),
+        }
+    }
+    // Starts a grab. A press on a node that is not selected selects it at once, so it can be
+    // dragged straight away.
+    fn press(&mut self, hit: Option<ID>, shift: bool, origin: Option<Pos2>) {
+        self.grab = match hit {
+            Some(id) => {
+                let selected = !self.selected.contains(&id);
+                if selected {
+                    self.click(Some(id), shift);
+                }
+                Some(Grab::Nodes { selected })
+            }
+            None if shift => origin.map(|o| Grab::Box(o, o)),
+            None => Some(Grab::Pan),
+        };
+    }
+    // the click ending a press, unless the press already selected the node.
+    fn release(&mut self, hit: Option<ID>, shift: bool) {
+        if self.grab != Some(Grab::Nodes { selected: true }) {
+            self.click(hit, shift);
+        }
+    }
+    fn move_selected(&mut self, dx: Scalar, dy: Scalar) {
+        let shift = Vect::new(dx, dy, 0.);
+        for id in self.selected.iter() {
+            if let Some(p) = self.points_to_display.get_mut(id) {
+                *p += shift;
+            }
+        }
+        // routes around the old positions no longer fit, those edges go straight.
+        let g = &self.g;
+        let selected = &self.selected;
+        self.bends.retain(|e, _| {
+            g.edges
+                .get(e)
+                .is_none_or(|e| !selected.contains(&e.left) && !selected.contains(&e.right))
         });
-        c
+        self.index = None;
+    }
+    // hover, clicks and drags in the flat view.
+    fn flat_pointer(&mut self, ui: &Ui, response: &Response) {
+        let index = self
+            .index
+            .get_or_insert_with(|| KdTree::new(&self.points_to_display));
+        let view = self.view;
+        let (g, style) = (&self.g, &self.style);
+        let radius = |id: &ID| PICK_RADIUS.max(style.node(&g.nodes[id]).radius as Scalar);
+        let reach = g.nodes.keys().map(radius).fold(PICK_RADIUS, Scalar::max);
+        let pick = |p: Pos2| view.pick(index, (p.x as Scalar, p.y as Scalar), radius, reach);
+        let input = ui.input();
+        let shift = input.modifiers.shift;
+        self.hovered = response.hover_pos().and_then(pick);
+
+        let origin = input.pointer.press_origin();
+        let pressed =
+            (input.pointer.any_pressed() && response.hovered()).then(|| origin.and_then(pick));
+        let clicked = response
+            .clicked()
+            .then(|| response.interact_pointer_pos().and_then(pick));
+        if let Some(hit) = pressed {
+            self.press(hit, shift, origin);
+        }
+        if let Some(hit) = clicked {
+            self.release(hit, shift);
+        }
+        if response.dragged() {
+            let d = response.drag_delta();
+            let (dx, dy) = (d.x as Scalar, d.y as Scalar);
+            match self.grab {
+                Some(Grab::Nodes { .. }) => self.move_selected(dx / view.scale, dy / view.scale),
+                Some(Grab::Box(start, _)) => {
+                    let end = input.pointer.interact_pos().unwrap_or(start);
+                    self.grab = Some(Grab::Box(start, end));
+                }
+                Some(Grab::Pan) | None => self.view.pan(dx, dy),
+            }
+        }
+        if !input.pointer.any_down() {
+            if let Some(Grab::Box(a, b)) = self.grab {
+                let index = self
+                    .index
+                    .get_or_insert_with(|| KdTree::new(&self.points_to_display));
+                let inside = view.pick_box(
+                    index,
+                    (a.x as Scalar, a.y as Scalar),
+                    (b.x as Scalar, b.y as Scalar),
+                );
+                self.selected.extend(inside);
+            }
+            self.grab = None;
+        }
+    }
+    fn fit_to(&mut self, rect: Rect) {
+        self.fit_pending = false;
+        if let Some(camera) = self.camera.as_mut() {
+            let aspect = (rect.width() / rect.height()) as Scalar;
+            let framed = OrbitCamera::framing(self.points_to_display.values(), camera.fov, aspect);
+            camera.target = framed.target;
+            camera.distance = framed.distance;
+            return;
+        }
+        if let Some(b) = BoundingBox::of_points(self.points_to_display.values()) {
+            let screen = BoundingBox::new(
+                Vect::new(rect.left() as Scalar, rect.top() as Scalar, 0.),
+                Vect::new(rect.right() as Scalar, rect.bottom() as Scalar, 0.),
+            );
+            self.view = FlatView::fit(&b, &screen, FIT_MARGIN);
+        }
+    }
+    // wheel and pinch zoom at the pointer while it is over the view.
+    fn pointer_zoom(ui: &Ui, response: &Response) -> Option<(Scalar, Pos2)> {
+        let at = response.hover_pos()?;
+        let input = ui.input();
+        let factor =
+            (input.scroll_delta.y as Scalar * ZOOM_SPEED).exp() * input.zoom_delta() as Scalar;
+        (factor != 1.0).then_some((factor, at))
+    }
+    fn show_flat(&mut self, ui: &mut Ui, keys: Shortcuts) {
+        let rect = ui.available_rect_before_wrap();
+        let response = ui.interact(rect, ui.id().with("pan"), Sense::click_and_drag());
+        if keys.fit || self.fit_pending {
+            self.fit_to(rect);
+        }
+        self.flat_pointer(ui, &response);
+        self.view
+            .pan(-keys.pan.0 * PAN_STEP, -keys.pan.1 * PAN_STEP);
+        let centre = rect.center();
+        self.view
+            .zoom_at(keys.zoom, centre.x as Scalar, centre.y as Scalar);
+        if let Some((factor, at)) = Self::pointer_zoom(ui, &response) {
+            self.view.zoom_at(factor, at.x as Scalar, at.y as Scalar);
+        }
+
+        let painter = ui.painter_at(rect);
+        for (id, v) in self.points_to_display.iter() {
+            painter.add(self.convert_vect_to_circle(id, v));
+        }
+        let routes = self.routes();
+        for (_, style, route) in routes.iter() {
+            for l in route.iter().copied().tuple_windows() {
+                painter.add(self.convert_to_line(&l, style));
+            }
+        }
+        let view = self.view;
+        self.paint_labels(&painter, &routes, |v, radius| {
+            let (x, y) = view.screen_point(v);
+            Some((pos(x, y), radius))
+        });
+        if let Some(Grab::Box(a, b)) = self.grab {
+            painter.rect_stroke(Rect::from_two_pos(a, b), 0.0, HOVER_STROKE);
+        }
+    }
+    // Edges and nodes are painted far to near so closer ones cover those behind, and
+    // shrink and darken with depth.
+    fn show_3d(&mut self, ui: &mut Ui, keys: Shortcuts) {
+        let rect = ui.available_rect_before_wrap();
+        let response = ui.interact(rect, ui.id().with("orbit"), Sense::click_and_drag());
+        if keys.fit || self.fit_pending {
+            self.fit_to(rect);
+        }
+        let zoom = Self::pointer_zoom(ui, &response).map_or(1.0, |(factor, _)| factor);
+        let camera = match self.camera.as_mut() {
+            Some(camera) => camera,
+            None => return,
+        };
+        if keys.reset {
+            camera.yaw = 0.0;
+            camera.pitch = 0.0;
+        }
+        if response.dragged() {
+            let d = response.drag_delta();
+            camera.orbit(-d.x as Scalar * ORBIT_SPEED, d.y as Scalar * ORBIT_SPEED);
+        }
+        camera.orbit(-keys.pan.0 * ORBIT_STEP, keys.pan.1 * ORBIT_STEP);
+        // zooming in brings the camera closer.
+        camera.zoom(1.0 / (zoom * keys.zoom));
+        let camera = *camera;
+
+        let (width, height) = (rect.width() as Scalar, rect.height() as Scalar);
+        let nearness = |depth: Scalar| (camera.distance / depth) as f32;
+        let scaled = |radius: f32, depth: Scalar| {
+            (radius * nearness(depth)).clamp(radius.min(1.0), 4.0 * radius)
+        };
+        let (g, style) = (&self.g, &self.style);
+        let radius = |id: &ID, depth: Scalar| {
+            PICK_RADIUS.max(scaled(style.node(&g.nodes[id]).radius, depth) as Scalar)
+        };
+        let pick = |p: Pos2| {
+            let at = ((p.x - rect.left()) as Scalar, (p.y - rect.top()) as Scalar);
+            camera.pick(&self.points_to_display, at, (width, height), radius)
+        };
+        self.hovered = response.hover_pos().and_then(pick);
+        if response.clicked() {
+            let hit = response.interact_pointer_pos().and_then(pick);
+            self.click(hit, ui.input().modifiers.shift);
+        }
+        let project = |v: &Vect| camera.project(v, width, height);
+        let to_pos =
+            |x: Scalar, y: Scalar| Pos2::new(rect.left() + x as f32, rect.top() + y as f32);
+        let shade = |depth: Scalar| nearness(depth).clamp(0.3, 1.0);
+
+        let mut shapes: Vec<(Scalar, Shape)> = Vec::new();
+        let routes = self.routes();
+        for (_, style, route) in routes.iter() {
+            for (s, e) in route.iter().tuple_windows() {
+                if let (Some(a), Some(b)) = (project(s), project(e)) {
+                    let depth = (a.depth + b.depth) / 2.0;
+                    let line = LineSegment {
+                        points: [to_pos(a.x, a.y), to_pos(b.x, b.y)],
+                        stroke: Stroke {
+                            width: style.width * nearness(depth),
+                            color: dim(style.colour, shade(depth)),
+                        },
+                    };
+                    shapes.push((depth, line));
+                }
+            }
+        }
+        for (id, v) in self.points_to_display.iter() {
+            if let Some(p) = project(v) {
+                let mut style = self.node_style(id);
+                style.radius = scaled(style.radius, p.depth);
+                style.fill = dim(style.fill, shade(p.depth));
+                let circle = self.node_circle(id, to_pos(p.x, p.y), style);
+                shapes.push((p.depth, circle));
+            }
+        }
+        shapes.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let painter = ui.painter_at(rect);
+        painter.extend(shapes.into_iter().map(|(_, shape)| shape).collect());
+        // labels go on top of everything, they are no use hidden.
+        self.paint_labels(&painter, &routes, |v, radius| {
+            project(v).map(|p| (to_pos(p.x, p.y), scaled(radius, p.depth)))
+        });
+    }
+    fn tooltip(&self, ctx: &CtxRef) {
+        let id = match self.hovered {
+            Some(id) if self.grab.is_none() => id,
+            _ => return,
+        };
+        if let Some(node) = self.g.nodes.get(&id) {
+            show_tooltip_at_pointer(ctx, eframe::egui::Id::new("node tooltip"), |ui| {
+                ui.label(format!("node {:?}", id));
+                ui.label(format!("data: {:?}", node.data));
+            });
+        }
+    }
+    fn inspector(&self, ui: &mut Ui) {
+        ui.heading("Selection");
+        match self.selected.len() {
+            0 => {
+                ui.label("Click a node to inspect it.");
+                ui.label("Shift click adds to the selection, shift drag selects a box.");
+            }
+            1 => {
+                let id = *self.selected.iter().next().unwrap();
+                self.node_details(ui, id);
+            }
+            n => {
+                ui.label(format!("{} nodes", n));
+                for id in self.selected.iter() {
+                    ui.label(format!("{:?}", id));
+                }
+            }
+        }
+    }
+    fn node_details(&self, ui: &mut Ui, id: ID) {
+        let node = match self.g.nodes.get(&id) {
+            Some(node) => node,
+            None => return,
+        };
+        ui.label(format!("id: {:?}", id));
+        ui.label(format!("data: {:?}", node.data));
+        ui.label(format!("degree: {}", self.g.degree(id).unwrap()));
+        ui.separator();
+        ui.label("edges:");
+        for e in self.g.incident_edges(id).unwrap() {
+            let other = if e.left == id { e.right } else { e.left };
+            ui.label(format!("{:?} to {:?}, data: {:?}", e.id, other, e.data));
+        }
     }
 }
 
-impl<T, E, ID: Debug + Copy + Ord + Clone + Hash + Eq> App for GraphDisplay<T, E, ID> {
-    fn update(&mut self, ctx: &CtxRef, frame: &Frame) {
+impl<T: Debug, E: Debug, ID: Debug + Copy + Ord + Clone + Hash + Eq> App
+    for GraphDisplay<T, E, ID>
+{
+    fn update(&mut self, ctx: &CtxRef, _frame: &Frame) {
+        SidePanel::right("inspector").show(ctx, |ui| {
+            ScrollArea::vertical().show(ui, |ui| self.inspector(ui));
+        });
         CentralPanel::default().show(ctx, |ui| {
-            // let c = Shape::Circle(CircleShape {
-            //     center: Pos2::new(500., 500.),
-            //     radius: 5.0,
-            //     fill: Color32::WHITE,
-            //     stroke: Default::default(),
-            // });
-            // let vecs = self.layout();
-            for vec in self.points_to_display.values() {
-                let c = GraphDisplay::<T, E, ID>::convert_vect_to_circle(&vec);
-                ui.painter().add(c);
-            }
-            for l in self.connections(&self.points_to_display) {
-                ui.painter().add(self.convert_to_line(&l));
+            let keys = Shortcuts::read(ui);
+            if self.camera.is_some() {
+                self.show_3d(ui, keys);
+            } else {
+                self.show_flat(ui, keys);
             }
         });
+        self.tooltip(ctx);
     }
 
     fn name(&self) -> &str {
@@ -136,11 +601,24 @@ fn test_graph() -> Graph<i32, i32, i32> {
 fn main() {
     //test_graph(); //
     let graph: Graph<i32, i32, i32> = create_random_graph::<i32, i32, i32>(20, 50, 1, 10, 0, 1);
-    let p: HashMap<i32, Vect> = algorithms::layout(&graph);
-    let app = GraphDisplay {
-        g: graph,
-        points_to_display: p,
+    // `--3d` lays the graph out in space and opens the orbit view.
+    let three_d = std::env::args().any(|a| a == "--3d");
+    let config = LayoutConfig::new().three_d(three_d);
+    let p: HashMap<i32, Vect> = component_layout(&graph, &config, COMPONENT_SPACING);
+    let camera = if three_d {
+        // a square guess, fit_pending refits to the viewport on the first frame.
+        Some(OrbitCamera::framing(p.values(), DEFAULT_FOV, 1.0))
+    } else {
+        None
     };
+    let style = Style::new()
+        .edge_width_by_weight(0.1)
+        .label_node_ids()
+        .label_edge_weights();
+    let app = GraphDisplay::new(graph, p, camera, style);
     let win_option = NativeOptions::default();
     run_native(Box::new(app), win_option);
 }
+
+#[cfg(test)]
+mod maintests;
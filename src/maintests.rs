@@ -0,0 +1,54 @@
+use crate::geometry::{Scalar, Vect};
+use crate::graph::Graph;
+use crate::style::Style;
+use crate::GraphDisplay;
+use std::collections::{BTreeSet, HashMap};
+
+fn display(selected: &[i32]) -> GraphDisplay<i32, i32, i32> {
+    let mut g: Graph<i32, i32, i32> = Graph::new();
+    let mut points = HashMap::new();
+    for n in [1, 2, 3] {
+        g.add_node(n, 0);
+        points.insert(n, Vect::new(n as Scalar, 0., 0.));
+    }
+    let mut d = GraphDisplay::new(g, points, None, Style::new());
+    d.selected = selected.iter().copied().collect();
+    d
+}
+
+// a press and release at the same place, then the button is up again.
+fn click(d: &mut GraphDisplay<i32, i32, i32>, hit: Option<i32>, shift: bool) {
+    d.press(hit, shift, None);
+    d.release(hit, shift);
+    d.grab = None;
+}
+
+#[test]
+fn press_and_release_select_once() {
+    // shift adds an unselected node and removes a selected one.
+    let mut d = display(&[2]);
+    click(&mut d, Some(1), true);
+    assert_eq!(BTreeSet::from([1, 2]), d.selected);
+    click(&mut d, Some(2), true);
+    assert_eq!(BTreeSet::from([1]), d.selected);
+
+    // without shift the node clicked on ends up the only one selected.
+    let mut d = display(&[2, 3]);
+    click(&mut d, Some(1), false);
+    assert_eq!(BTreeSet::from([1]), d.selected);
+    let mut d = display(&[2, 3]);
+    click(&mut d, Some(2), false);
+    assert_eq!(BTreeSet::from([2]), d.selected);
+
+    // a press on an unselected node selects it before the drag starts.
+    let mut d = display(&[2]);
+    d.press(Some(1), true, None);
+    assert_eq!(BTreeSet::from([1, 2]), d.selected);
+
+    // clicking on nothing clears the selection, unless shift is held.
+    let mut d = display(&[2, 3]);
+    click(&mut d, None, true);
+    assert_eq!(BTreeSet::from([2, 3]), d.selected);
+    click(&mut d, None, false);
+    assert!(d.selected.is_empty());
+}
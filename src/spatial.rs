@@ -0,0 +1,159 @@
+use crate::geometry::{BoundingBox, Scalar, Vect};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// k-d tree over node positions for hit testing and proximity queries. The tree is implicit:
+// every range of `items` keeps its median at the middle, with the points before it on the
+// low side of the split and those after on the high side. Splits go along the axis the
+// points spread most, so flat layouts never split on z. Ties are broken by id.
+#[derive(Debug, Clone)]
+pub struct KdTree<ID> {
+    items: Vec<(ID, Vect)>,
+    axes: Vec<usize>,
+}
+
+fn coordinate(v: &Vect, axis: usize) -> Scalar {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+fn build<ID: Copy + Ord>(items: &mut [(ID, Vect)], axes: &mut [usize]) {
+    if items.is_empty() {
+        return;
+    }
+    let b = BoundingBox::of_points(items.iter().map(|(_, p)| p)).unwrap();
+    let spread = [b.width(), b.height(), b.depth()];
+    let axis = (0..3)
+        .max_by(|a, c| spread[*a].total_cmp(&spread[*c]).then(c.cmp(a)))
+        .unwrap();
+    let mid = items.len() / 2;
+    items.select_nth_unstable_by(mid, |a, c| {
+        coordinate(&a.1, axis)
+            .total_cmp(&coordinate(&c.1, axis))
+            .then(a.0.cmp(&c.0))
+    });
+    axes[mid] = axis;
+    let (low, rest) = items.split_at_mut(mid);
+    let (low_axes, rest_axes) = axes.split_at_mut(mid);
+    build(low, low_axes);
+    build(&mut rest[1..], &mut rest_axes[1..]);
+}
+
+impl<ID: Copy + Ord + Hash + Eq> KdTree<ID> {
+    pub fn new(positions: &HashMap<ID, Vect>) -> Self {
+        Self::from_points(positions.iter().map(|(id, p)| (*id, *p)).collect())
+    }
+    pub fn from_points(points: Vec<(ID, Vect)>) -> Self {
+        let mut items: Vec<(ID, Vect)> = points.into_iter().sorted_by_key(|(id, _)| *id).collect();
+        let mut axes = vec![0; items.len()];
+        build(&mut items, &mut axes);
+        Self { items, axes }
+    }
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn nearest(&self, p: &Vect) -> Option<(ID, Scalar)> {
+        self.k_nearest(p, 1).into_iter().next()
+    }
+    // the k nearest nodes with their distances, nearest first.
+    pub fn k_nearest(&self, p: &Vect, k: usize) -> Vec<(ID, Scalar)> {
+        let mut best: Vec<(Scalar, ID)> = Vec::with_capacity(k + 1);
+        if k > 0 {
+            self.nearest_in(0, self.items.len(), p, k, &mut best);
+        }
+        best.into_iter().map(|(d, id)| (id, d)).collect()
+    }
+    fn nearest_in(&self, lo: usize, hi: usize, p: &Vect, k: usize, best: &mut Vec<(Scalar, ID)>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let (id, q) = self.items[mid];
+        let d = p.euclid_distance(&q);
+        let candidate = (d, id);
+        let worse = |a: &(Scalar, ID), b: &(Scalar, ID)| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1));
+        if best.len() < k || worse(&candidate, best.last().unwrap()).is_lt() {
+            let at = best.partition_point(|b| worse(b, &candidate).is_lt());
+            best.insert(at, candidate);
+            best.truncate(k);
+        }
+
+        let axis = self.axes[mid];
+        let offset = coordinate(p, axis) - coordinate(&q, axis);
+        let (near, far) = if offset < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.nearest_in(near.0, near.1, p, k, best);
+        if best.len() < k || offset.abs() <= best.last().unwrap().0 {
+            self.nearest_in(far.0, far.1, p, k, best);
+        }
+    }
+
+    // every node at most `radius` from p, in id order.
+    pub fn within_radius(&self, p: &Vect, radius: Scalar) -> Vec<ID> {
+        let mut found: Vec<ID> = self
+            .within_radius_by_distance(p, radius)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        found.sort();
+        found
+    }
+    // every node at most `radius` from p with its distance, nearest first and ties by id.
+    pub fn within_radius_by_distance(&self, p: &Vect, radius: Scalar) -> Vec<(ID, Scalar)> {
+        let mut found = Vec::new();
+        let b = BoundingBox::new(*p, *p).expand(radius);
+        self.within_box_in(0, self.items.len(), &b, &mut |id, q| {
+            let d = p.euclid_distance(q);
+            if d <= radius {
+                found.push((id, d));
+            }
+        });
+        found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        found
+    }
+    // every node inside the box, borders included, in id order.
+    pub fn within_box(&self, b: &BoundingBox) -> Vec<ID> {
+        let mut found = Vec::new();
+        self.within_box_in(0, self.items.len(), b, &mut |id, _| found.push(id));
+        found.sort();
+        found
+    }
+    fn within_box_in<F: FnMut(ID, &Vect)>(
+        &self,
+        lo: usize,
+        hi: usize,
+        b: &BoundingBox,
+        visit: &mut F,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let (id, q) = self.items[mid];
+        if b.contains(&q) {
+            visit(id, &q);
+        }
+        let axis = self.axes[mid];
+        let split = coordinate(&q, axis);
+        if coordinate(&b.min, axis) <= split {
+            self.within_box_in(lo, mid, b, visit);
+        }
+        if coordinate(&b.max, axis) >= split {
+            self.within_box_in(mid + 1, hi, b, visit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod spatialtests;
@@ -0,0 +1,88 @@
+use crate::generators::seeded_rng;
+use crate::geometry::{BoundingBox, Scalar, Vect};
+use crate::spatial::KdTree;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+fn scattered(n: u32, use_z: bool) -> HashMap<u32, Vect> {
+    let mut rng = seeded_rng(21);
+    (0..n)
+        .map(|id| (id, Vect::random_from(&mut rng, -10., 10., use_z)))
+        .collect()
+}
+
+// every node by distance to p, ties by id.
+fn by_distance(positions: &HashMap<u32, Vect>, p: &Vect) -> Vec<(u32, Scalar)> {
+    positions
+        .iter()
+        .map(|(id, q)| (*id, p.euclid_distance(q)))
+        .sorted_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+        .collect()
+}
+
+#[test]
+fn nearest_matches_brute_force() {
+    for use_z in [false, true] {
+        let positions = scattered(300, use_z);
+        let tree = KdTree::new(&positions);
+        assert_eq!(300, tree.len());
+        let mut rng = seeded_rng(22);
+        for _ in 0..50 {
+            let p = Vect::random_from(&mut rng, -12., 12., use_z);
+            let all = by_distance(&positions, &p);
+            assert_eq!(Some(all[0]), tree.nearest(&p));
+            assert_eq!(all[..7].to_vec(), tree.k_nearest(&p, 7));
+        }
+        assert_eq!(300, tree.k_nearest(&Vect::new(0., 0., 0.), 500).len());
+    }
+}
+
+#[test]
+fn range_queries_match_brute_force() {
+    let positions = scattered(300, true);
+    let tree = KdTree::new(&positions);
+    let p = Vect::new(1., -2., 0.5);
+    for radius in [0.0, 1.5, 4.0, 30.0] {
+        let expected: Vec<u32> = positions
+            .iter()
+            .filter(|(_, q)| p.euclid_distance(q) <= radius)
+            .map(|(id, _)| *id)
+            .sorted()
+            .collect();
+        assert_eq!(expected, tree.within_radius(&p, radius));
+        let nearest: Vec<(u32, Scalar)> = by_distance(&positions, &p)
+            .into_iter()
+            .take_while(|(_, d)| *d <= radius)
+            .collect();
+        assert_eq!(nearest, tree.within_radius_by_distance(&p, radius));
+    }
+    let b = BoundingBox::new(Vect::new(-3., 0., -10.), Vect::new(5., 4., 10.));
+    let expected: Vec<u32> = positions
+        .iter()
+        .filter(|(_, q)| b.contains(q))
+        .map(|(id, _)| *id)
+        .sorted()
+        .collect();
+    assert!(!expected.is_empty());
+    assert_eq!(expected, tree.within_box(&b));
+}
+
+#[test]
+fn coincident_and_empty() {
+    let same: HashMap<u32, Vect> = (0..20).map(|id| (id, Vect::new(1., 1., 0.))).collect();
+    let tree = KdTree::new(&same);
+    assert_eq!(Some((0, 0.0)), tree.nearest(&Vect::new(1., 1., 0.)));
+    assert_eq!(
+        vec![0, 1, 2],
+        tree.k_nearest(&Vect::new(2., 2., 0.), 3)
+            .iter()
+            .map(|n| n.0)
+            .collect_vec()
+    );
+    assert_eq!(20, tree.within_radius(&Vect::new(1., 1., 0.), 0.0).len());
+
+    let empty: KdTree<u32> = KdTree::new(&HashMap::new());
+    assert!(empty.is_empty());
+    assert_eq!(None, empty.nearest(&Vect::new(0., 0., 0.)));
+    assert!(empty.within_radius(&Vect::new(0., 0., 0.), 1.0).is_empty());
+}
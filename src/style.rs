@@ -0,0 +1,151 @@
+use crate::geometry::{Scalar, Vect};
+use crate::graph::{Edge, Node, Weighted};
+use eframe::egui::{Color32, Stroke};
+use itertools::Itertools;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// How the viewer draws nodes and edges. Every property is a closure over the node or edge,
+// data included, so the drawing can follow the data; the defaults are the plain white
+// drawing without labels. Sizes are in pixels.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeStyle {
+    pub fill: Color32,
+    pub radius: f32,
+    pub stroke: Stroke,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeStyle {
+    pub colour: Color32,
+    pub width: f32,
+}
+
+type NodeFn<T, ID, R> = Box<dyn Fn(&Node<T, ID>) -> R>;
+type EdgeFn<E, ID, R> = Box<dyn Fn(&Edge<E, ID>) -> R>;
+
+pub struct Style<T, E, ID: Clone + Hash + Eq> {
+    node_fill: NodeFn<T, ID, Color32>,
+    node_radius: NodeFn<T, ID, f32>,
+    node_stroke: NodeFn<T, ID, Stroke>,
+    edge_colour: EdgeFn<E, ID, Color32>,
+    edge_width: EdgeFn<E, ID, f32>,
+    node_label: Option<NodeFn<T, ID, String>>,
+    edge_label: Option<EdgeFn<E, ID, String>>,
+    pub label_colour: Color32,
+}
+
+impl<T, E, ID: Clone + Hash + Eq> Default for Style<T, E, ID> {
+    fn default() -> Self {
+        Self {
+            node_fill: Box::new(|_| Color32::WHITE),
+            node_radius: Box::new(|_| 5.0),
+            node_stroke: Box::new(|_| Stroke::none()),
+            edge_colour: Box::new(|_| Color32::WHITE),
+            edge_width: Box::new(|_| 0.1),
+            node_label: None,
+            edge_label: None,
+            label_colour: Color32::LIGHT_GRAY,
+        }
+    }
+}
+
+impl<T, E, ID: Clone + Hash + Eq> Style<T, E, ID> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn node_fill(mut self, f: impl Fn(&Node<T, ID>) -> Color32 + 'static) -> Self {
+        self.node_fill = Box::new(f);
+        self
+    }
+    pub fn node_radius(mut self, f: impl Fn(&Node<T, ID>) -> f32 + 'static) -> Self {
+        self.node_radius = Box::new(f);
+        self
+    }
+    pub fn node_stroke(mut self, f: impl Fn(&Node<T, ID>) -> Stroke + 'static) -> Self {
+        self.node_stroke = Box::new(f);
+        self
+    }
+    pub fn edge_colour(mut self, f: impl Fn(&Edge<E, ID>) -> Color32 + 'static) -> Self {
+        self.edge_colour = Box::new(f);
+        self
+    }
+    pub fn edge_width(mut self, f: impl Fn(&Edge<E, ID>) -> f32 + 'static) -> Self {
+        self.edge_width = Box::new(f);
+        self
+    }
+    pub fn node_labels(mut self, f: impl Fn(&Node<T, ID>) -> String + 'static) -> Self {
+        self.node_label = Some(Box::new(f));
+        self
+    }
+    pub fn edge_labels(mut self, f: impl Fn(&Edge<E, ID>) -> String + 'static) -> Self {
+        self.edge_label = Some(Box::new(f));
+        self
+    }
+    pub fn label_colour(mut self, c: Color32) -> Self {
+        self.label_colour = c;
+        self
+    }
+
+    pub fn node(&self, n: &Node<T, ID>) -> NodeStyle {
+        NodeStyle {
+            fill: (self.node_fill)(n),
+            radius: (self.node_radius)(n).max(0.0),
+            stroke: (self.node_stroke)(n),
+        }
+    }
+    pub fn edge(&self, e: &Edge<E, ID>) -> EdgeStyle {
+        EdgeStyle {
+            colour: (self.edge_colour)(e),
+            width: (self.edge_width)(e).max(0.0),
+        }
+    }
+    pub fn node_label(&self, n: &Node<T, ID>) -> Option<String> {
+        self.node_label.as_ref().map(|f| f(n))
+    }
+    pub fn edge_label(&self, e: &Edge<E, ID>) -> Option<String> {
+        self.edge_label.as_ref().map(|f| f(e))
+    }
+}
+
+impl<T: Debug, E, ID: Debug + Clone + Hash + Eq> Style<T, E, ID> {
+    pub fn label_node_ids(self) -> Self {
+        self.node_labels(|n| format!("{:?}", n.id))
+    }
+    pub fn label_node_data(self) -> Self {
+        self.node_labels(|n| format!("{:?}", n.data))
+    }
+}
+
+impl<T, E: Weighted, ID: Clone + Hash + Eq> Style<T, E, ID> {
+    // `scale` pixels per unit of weight, negative weights drawn as thin as possible.
+    pub fn edge_width_by_weight(self, scale: f32) -> Self {
+        self.edge_width(move |e| e.data.weight() as f32 * scale)
+    }
+    pub fn label_edge_weights(self) -> Self {
+        self.edge_labels(|e| e.data.weight().to_string())
+    }
+}
+
+// the point halfway along a route, where its label goes.
+pub fn label_point(route: &[Vect]) -> Option<Vect> {
+    let first = *route.first()?;
+    let length: Scalar = route
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| a.euclid_distance(b))
+        .sum();
+    let mut left = length / 2.0;
+    for (a, b) in route.iter().tuple_windows() {
+        let d = a.euclid_distance(b);
+        if d > 0.0 && left <= d {
+            return Some(a.lerp(b, left / d));
+        }
+        left -= d;
+    }
+    Some(first)
+}
+
+#[cfg(test)]
+mod styletests;
@@ -0,0 +1,58 @@
+use crate::geometry::Vect;
+use crate::graph::{Edge, Node};
+use crate::style::{label_point, Style};
+use eframe::egui::{Color32, Stroke};
+
+#[test]
+fn defaults_are_the_plain_drawing() {
+    let style: Style<i32, i32, i32> = Style::new();
+    let node = style.node(&Node::new(1, 7));
+    assert_eq!(node.fill, Color32::WHITE);
+    assert_eq!(node.radius, 5.0);
+    assert_eq!(node.stroke, Stroke::none());
+    assert_eq!(style.edge(&Edge::new(1, 3, 1, 2)).colour, Color32::WHITE);
+    assert_eq!(style.node_label(&Node::new(1, 7)), None);
+    assert_eq!(style.edge_label(&Edge::new(1, 3, 1, 2)), None);
+}
+
+#[test]
+fn closures_follow_the_data() {
+    let style: Style<i32, i32, char> = Style::new()
+        .node_fill(|n| {
+            if n.data > 0 {
+                Color32::RED
+            } else {
+                Color32::BLUE
+            }
+        })
+        .node_radius(|n| n.data as f32)
+        .edge_width_by_weight(0.5)
+        .label_node_ids()
+        .label_edge_weights();
+    let big = Node::new('a', 8);
+    let small = Node::new('b', -1);
+    assert_eq!(style.node(&big).fill, Color32::RED);
+    assert_eq!(style.node(&small).fill, Color32::BLUE);
+    assert_eq!(style.node(&big).radius, 8.0);
+    // sizes never go below zero.
+    assert_eq!(style.node(&small).radius, 0.0);
+    assert_eq!(style.edge(&Edge::new('e', 6, 'a', 'b')).width, 3.0);
+    assert_eq!(style.node_label(&big), Some("'a'".to_string()));
+    assert_eq!(
+        style.edge_label(&Edge::new('e', 6, 'a', 'b')),
+        Some("6".to_string())
+    );
+}
+
+#[test]
+fn labels_sit_halfway_along_routes() {
+    let route = [
+        Vect::new(0., 0., 0.),
+        Vect::new(2., 0., 0.),
+        Vect::new(2., 4., 0.),
+    ];
+    assert_eq!(label_point(&route), Some(Vect::new(2., 1., 0.)));
+    let p = Vect::new(1., 1., 1.);
+    assert_eq!(label_point(&[p, p]), Some(p));
+    assert_eq!(label_point(&[]), None);
+}
@@ -1,47 +1,148 @@
+use crate::generators::random::random_graph;
+use crate::generators::seeded_rng;
+use crate::geometry::Vect;
 use crate::graph::{Edge, Node};
 use crate::Graph;
 use rand::Rng;
 
 use itertools::Itertools;
 use regex::Regex;
-use std::fmt::Debug;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::hash::Hash;
-use std::io::{BufRead, BufReader, Write};
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
+use std::str::FromStr;
 
-pub fn create_random_graph<T: Default, E: Default, ID: Copy + Clone + Hash + Eq>(
-    min_nodes: i32,
-    max_nodes: i32,
-    min_weight: i32,
-    max_weight: i32,
-    min_node_data: i32,
-    max_node_data: i32,
-) -> Graph<i32, i32, i32> {
-    //
-    let mut g: Graph<i32, i32, i32> = Graph::new();
-    let mut rng = rand::thread_rng();
+pub mod adjlist;
+pub mod edgelist;
+pub mod formats;
+pub mod gexf;
+pub mod gml;
+pub mod matrix;
+pub mod pajek;
+pub mod snapshot;
+
+#[derive(Debug)]
+pub struct FormatError {
+    pub line: Option<usize>,
+    message: String,
+}
 
-    let nodes = rng.gen_range(min_nodes..max_nodes);
-    for x in 0..nodes - 1 {
-        g.add_node(x, rng.gen_range(min_node_data..max_node_data));
+impl FormatError {
+    pub(crate) fn new(m: &str) -> Self {
+        Self {
+            line: None,
+            message: m.to_string(),
+        }
     }
-    let edges = 2 * nodes; //rng.gen_range(min_nodes..max_nodes);
-    for ix in 0..edges {
-        let mut l = rng.gen_range(0..nodes - 1);
-        let mut r = rng.gen_range(0..nodes - 1);
-        while l == r {
-            l = rng.gen_range(0..nodes - 1);
-            r = rng.gen_range(0..nodes - 1);
+    pub(crate) fn at_line(line: usize, m: &str) -> Self {
+        Self {
+            line: Some(line),
+            message: m.to_string(),
         }
-        let w = rng.gen_range(min_weight..max_weight);
+    }
+}
 
-        if !g.connected(&l, &r) {
-            let _ = g.add_edge(ix, l, r, w);
+impl Display for FormatError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.line {
+            Some(l) => write!(f, "line {}: {}", l, self.message),
+            None => write!(f, "{}", self.message),
         }
     }
+}
 
-    g
+impl std::error::Error for FormatError {}
+
+impl From<io::Error> for FormatError {
+    fn from(e: io::Error) -> Self {
+        FormatError::new(&e.to_string())
+    }
+}
+
+//everything after a '#' or '%' is a comment (SNAP uses '#', KONECT uses '%').
+pub(crate) fn strip_comment(line: &str) -> &str {
+    match line.find(['#', '%']) {
+        Some(ix) => &line[..ix],
+        None => line,
+    }
+}
+
+pub(crate) fn parse_token<V: FromStr>(
+    token: &str,
+    line: usize,
+    what: &str,
+) -> Result<V, FormatError> {
+    token
+        .parse()
+        .map_err(|_| FormatError::at_line(line, &format!("invalid {} '{}'.", what, token)))
+}
+
+pub(crate) fn escape_entities(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub(crate) fn unescape_entities(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+//text formats rarely carry edge ids, so edges are numbered in the order they are read.
+pub(crate) fn edge_id_from<ID: TryFrom<usize>>(ix: usize, line: usize) -> Result<ID, FormatError> {
+    ID::try_from(ix).map_err(|_| FormatError::at_line(line, "edge id does not fit the id type."))
+}
+
+pub fn create_random_graph<
+    T: From<i32>,
+    E: From<i32>,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+>(
+    min_nodes: i32,
+    max_nodes: i32,
+    min_weight: i32,
+    max_weight: i32,
+    min_node_data: i32,
+    max_node_data: i32,
+) -> Graph<T, E, ID> {
+    //
+    random_graph(
+        &mut rand::thread_rng(),
+        min_nodes.max(0) as usize,
+        max_nodes.max(0) as usize,
+        |rng| T::from(rng.gen_range(min_node_data..max_node_data)),
+        |rng| E::from(rng.gen_range(min_weight..max_weight)),
+    )
+}
+pub fn create_seeded_random_graph<
+    T: From<i32>,
+    E: From<i32>,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+>(
+    seed: u64,
+    min_nodes: i32,
+    max_nodes: i32,
+    min_weight: i32,
+    max_weight: i32,
+    min_node_data: i32,
+    max_node_data: i32,
+) -> Graph<T, E, ID> {
+    random_graph(
+        &mut seeded_rng(seed),
+        min_nodes.max(0) as usize,
+        max_nodes.max(0) as usize,
+        |rng| T::from(rng.gen_range(min_node_data..max_node_data)),
+        |rng| E::from(rng.gen_range(min_weight..max_weight)),
+    )
 }
 pub fn from_viz_dot<T: Default, E: Default, ID: Debug + Copy + Clone + Hash + Eq>(
     path: &str,
@@ -50,7 +151,8 @@ pub fn from_viz_dot<T: Default, E: Default, ID: Debug + Copy + Clone + Hash + Eq
     let reader = BufReader::new(File::open(path).expect("Cannot open file"));
     let mut g: Graph<i32, i32, i32> = Graph::new();
 
-    let edge_re = Regex::new(r"^\s*(\d*) -- (\d*) \[label=(\d*), id=(\d*)];").unwrap();
+    // edges written with a route carry more attributes after the id.
+    let edge_re = Regex::new(r"^\s*(\d*) -- (\d*) \[label=(\d*), id=(\d*)[,\]]").unwrap();
     let node_re = Regex::new(r"^\s*(\d*)\[.*?Data (\d*).*?;").unwrap();
 
     for line in reader.lines() {
@@ -69,8 +171,20 @@ pub fn to_viz_dot<T: Default + Debug, E: Debug, ID: Debug + Copy + Clone + Hash
     g: &Graph<T, E, ID>,
     path: &str,
 ) {
-    let path = Path::new(path);
-    let mut file = File::create(&path).unwrap();
+    let file = File::create(Path::new(path)).unwrap();
+    let _ = write_viz_dot(g, None, None, file);
+}
+
+// Positions become pinned `pos` attributes (for neato -n) and bend points become the
+// straight pieces of an edge spline. Graphviz has y pointing up, so y is flipped.
+pub fn write_viz_dot<T: Debug, E: Debug, ID: Debug + Copy + Clone + Hash + Eq + Ord, W: Write>(
+    g: &Graph<T, E, ID>,
+    positions: Option<&HashMap<ID, Vect>>,
+    bends: Option<&HashMap<ID, Vec<Vect>>>,
+    writer: W,
+) -> Result<(), FormatError> {
+    let mut writer = writer;
+    let point = |v: &Vect| format!("{},{}", v.x, 0.0 - v.y);
     let mut out_data = String::new();
     out_data.push_str("graph D {");
     out_data.push('\n');
@@ -80,7 +194,14 @@ pub fn to_viz_dot<T: Default + Debug, E: Debug, ID: Debug + Copy + Clone + Hash
             data,
             edges: _edges,
         } = g.nodes.get(node_key).unwrap();
-        let l = format!("    {:?}[label=\"{:?} \\nData {:?}\"];\r\n", id, id, data);
+        let pos = match positions.and_then(|p| p.get(id)) {
+            Some(v) => format!(", pos=\"{}!\"", point(v)),
+            None => String::new(),
+        };
+        let l = format!(
+            "    {:?}[label=\"{:?} \\nData {:?}\"{}];\r\n",
+            id, id, data, pos
+        );
         out_data.push_str(&l);
     }
 
@@ -93,17 +214,44 @@ pub fn to_viz_dot<T: Default + Debug, E: Debug, ID: Debug + Copy + Clone + Hash
             left,
             right,
         } = g.edges.get(edge_key).unwrap();
+        let route = positions.and_then(|p| {
+            let b = bends.and_then(|b| b.get(id))?;
+            let mut points = vec![p.get(left)?];
+            points.extend(b.iter());
+            points.push(p.get(right)?);
+            Some(points)
+        });
+        let pos = match route {
+            Some(points) => {
+                let mut spline = vec![point(points[0])];
+                for (a, b) in points.iter().tuple_windows() {
+                    spline.extend([point(a), point(b), point(b)]);
+                }
+                format!(", pos=\"{}\"", spline.join(" "))
+            }
+            None => String::new(),
+        };
         let l = format!(
-            "    {:?} -- {:?} [label={:?}, id={:?}];\r\n",
-            left, right, data, id
+            "    {:?} -- {:?} [label={:?}, id={:?}{}];\r\n",
+            left, right, data, id, pos
         );
         out_data.push_str(&l);
     }
 
     out_data.push('}');
-    out_data = out_data.replace("'", "");
-    let _ = file.write_all(out_data.as_bytes());
-    let _ = file.flush();
+    out_data = out_data.replace('\'', "");
+    writer.write_all(out_data.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn save_viz_dot<T: Debug, E: Debug, ID: Debug + Copy + Clone + Hash + Eq + Ord>(
+    g: &Graph<T, E, ID>,
+    positions: Option<&HashMap<ID, Vect>>,
+    bends: Option<&HashMap<ID, Vec<Vect>>>,
+    path: &str,
+) -> Result<(), FormatError> {
+    write_viz_dot(g, positions, bends, BufWriter::new(File::create(path)?))
 }
 fn match_edges(g: &mut Graph<i32, i32, i32>, edge_re: &Regex, line: &String) {
     let matches = edge_re.captures_iter(line.as_str());
@@ -127,3 +275,6 @@ fn match_nodes(g: &mut Graph<i32, i32, i32>, node_re: &Regex, line: &String) {
         }
     }
 }
+
+#[cfg(test)]
+mod utilstests;
@@ -0,0 +1,119 @@
+use crate::graph::Graph;
+use crate::utils::{edge_id_from, parse_token, strip_comment, FormatError};
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::str::FromStr;
+
+// Adjacency lists, one node per line followed by its neighbours:
+//
+//     # comment
+//     1 2 3
+//     2 3:0.5
+//     4
+//
+// A neighbour may carry edge data after a ':'. Edges listed from both ends are only added once.
+pub fn read_adjacency_list<T, E, ID, R>(reader: R) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: BufRead,
+{
+    let mut reader = reader;
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let mut seen: HashSet<(ID, ID)> = HashSet::new();
+    let mut line = String::new();
+    let mut line_no = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_no += 1;
+
+        let mut tokens = strip_comment(&line).split_whitespace();
+        let from: ID = match tokens.next() {
+            None => continue,
+            Some(t) => parse_token(t, line_no, "node id")?,
+        };
+        g.add_node(from, T::default());
+
+        for token in tokens {
+            let (to, data): (ID, E) = match token.split_once(':') {
+                None => (parse_token(token, line_no, "node id")?, E::default()),
+                Some((id, w)) => (
+                    parse_token(id, line_no, "node id")?,
+                    parse_token(w, line_no, "edge weight")?,
+                ),
+            };
+            if seen.contains(&(from, to)) || seen.contains(&(to, from)) {
+                continue;
+            }
+            seen.insert((from, to));
+
+            g.add_node(to, T::default());
+            let edge_id = edge_id_from(g.edges.len(), line_no)?;
+            let _ = g.add_edge(edge_id, from, to, data);
+        }
+    }
+    Ok(g)
+}
+
+pub fn load_adjacency_list<T, E, ID>(path: &str) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    read_adjacency_list(BufReader::new(File::open(path)?))
+}
+
+// Every node gets a line so isolated nodes survive a round trip; each edge is written once,
+// on the line of its 'left' node.
+pub fn write_adjacency_list<T, E, ID, W>(
+    g: &Graph<T, E, ID>,
+    writer: W,
+    weighted: bool,
+) -> Result<(), FormatError>
+where
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+    W: Write,
+{
+    let mut writer = writer;
+    for node_key in g.nodes.keys().sorted() {
+        let node = g.nodes.get(node_key).unwrap();
+        write!(writer, "{}", node.id)?;
+        for edge_id in node.edges.iter().sorted().dedup() {
+            let edge = g.edges.get(edge_id).unwrap();
+            if edge.left != node.id {
+                continue;
+            }
+            if weighted {
+                write!(writer, " {}:{}", edge.right, edge.data)?;
+            } else {
+                write!(writer, " {}", edge.right)?;
+            }
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn save_adjacency_list<T, E, ID>(
+    g: &Graph<T, E, ID>,
+    path: &str,
+    weighted: bool,
+) -> Result<(), FormatError>
+where
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+{
+    write_adjacency_list(g, BufWriter::new(File::create(path)?), weighted)
+}
@@ -0,0 +1,106 @@
+use crate::graph::Graph;
+use crate::utils::{edge_id_from, parse_token, strip_comment, FormatError};
+use itertools::Itertools;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::str::FromStr;
+
+// Whitespace separated edge lists as shipped by SNAP and KONECT:
+//
+//     # comment
+//     1 2
+//     2 3 0.5
+//
+// The optional third column is parsed as the edge data, any further columns are ignored.
+pub fn read_edge_list<T, E, ID, R>(reader: R) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: BufRead,
+{
+    let mut reader = reader;
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let mut line = String::new();
+    let mut line_no = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_no += 1;
+
+        let mut tokens = strip_comment(&line).split_whitespace();
+        let from: ID = match tokens.next() {
+            None => continue,
+            Some(t) => parse_token(t, line_no, "node id")?,
+        };
+        let to: ID = match tokens.next() {
+            None => {
+                return Err(FormatError::at_line(
+                    line_no,
+                    "expected a pair of node ids.",
+                ))
+            }
+            Some(t) => parse_token(t, line_no, "node id")?,
+        };
+        let data: E = match tokens.next() {
+            None => E::default(),
+            Some(t) => parse_token(t, line_no, "edge weight")?,
+        };
+
+        g.add_node(from, T::default());
+        g.add_node(to, T::default());
+        let edge_id = edge_id_from(g.edges.len(), line_no)?;
+        // both ends were just added so this cannot fail.
+        let _ = g.add_edge(edge_id, from, to, data);
+    }
+    Ok(g)
+}
+
+pub fn load_edge_list<T, E, ID>(path: &str) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    read_edge_list(BufReader::new(File::open(path)?))
+}
+
+pub fn write_edge_list<T, E, ID, W>(
+    g: &Graph<T, E, ID>,
+    writer: W,
+    weighted: bool,
+) -> Result<(), FormatError>
+where
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+    W: Write,
+{
+    let mut writer = writer;
+    for edge_key in g.edges.keys().sorted() {
+        let edge = g.edges.get(edge_key).unwrap();
+        if weighted {
+            writeln!(writer, "{} {} {}", edge.left, edge.right, edge.data)?;
+        } else {
+            writeln!(writer, "{} {}", edge.left, edge.right)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn save_edge_list<T, E, ID>(
+    g: &Graph<T, E, ID>,
+    path: &str,
+    weighted: bool,
+) -> Result<(), FormatError>
+where
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+{
+    write_edge_list(g, BufWriter::new(File::create(path)?), weighted)
+}
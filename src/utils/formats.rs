@@ -0,0 +1,120 @@
+use crate::graph::Graph;
+use crate::utils::adjlist::read_adjacency_list;
+use crate::utils::edgelist::read_edge_list;
+use crate::utils::gexf::read_gexf;
+use crate::utils::gml::read_gml;
+use crate::utils::matrix::read_matrix_market;
+use crate::utils::pajek::read_pajek;
+use crate::utils::{strip_comment, FormatError};
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GraphFormat {
+    Dot,
+    EdgeList,
+    AdjacencyList,
+    MatrixMarket,
+    Gml,
+    Pajek,
+    Gexf,
+}
+
+pub fn format_from_extension(path: &str) -> Option<GraphFormat> {
+    let ext = Path::new(path).extension()?.to_str()?.to_lowercase();
+    match &ext[..] {
+        "dot" | "gv" => Some(GraphFormat::Dot),
+        "edges" | "edgelist" | "el" | "tsv" => Some(GraphFormat::EdgeList),
+        "adj" | "adjlist" => Some(GraphFormat::AdjacencyList),
+        "mtx" => Some(GraphFormat::MatrixMarket),
+        "gml" => Some(GraphFormat::Gml),
+        "net" | "paj" => Some(GraphFormat::Pajek),
+        "gexf" => Some(GraphFormat::Gexf),
+        _ => None,
+    }
+}
+
+// Looks at the first meaningful line. Anything unrecognised is taken to be an edge list.
+// GML and undirected DOT both open with `graph`; what follows it decides, on a later line
+// if need be: `[` is GML, a name or `{` is DOT.
+pub fn format_from_header<R: BufRead>(reader: R) -> Result<GraphFormat, FormatError> {
+    let mut lines = reader.lines();
+    while let Some(line) = lines.next() {
+        let line = line?;
+        let trimmed = line.trim();
+        let lower = trimmed.to_lowercase();
+        if lower.starts_with("%%matrixmarket") {
+            return Ok(GraphFormat::MatrixMarket);
+        }
+        if lower.starts_with("<?xml") || lower.starts_with("<gexf") {
+            return Ok(GraphFormat::Gexf);
+        }
+        if lower.starts_with("*vertices") || lower.starts_with("*network") {
+            return Ok(GraphFormat::Pajek);
+        }
+        let content = strip_comment(&lower).trim();
+        if content.is_empty() {
+            continue;
+        }
+        let first = content.split_whitespace().next().unwrap_or("");
+        match first {
+            "digraph" | "strict" => return Ok(GraphFormat::Dot),
+            "creator" => return Ok(GraphFormat::Gml),
+            _ if first == "graph" || first.starts_with("graph[") || first.starts_with("graph{") => {
+                let mut rest = content["graph".len()..].trim().to_string();
+                while rest.is_empty() {
+                    match lines.next() {
+                        Some(next) => rest = strip_comment(next?.trim()).trim().to_string(),
+                        None => break,
+                    }
+                }
+                return Ok(if rest.starts_with('[') {
+                    GraphFormat::Gml
+                } else {
+                    GraphFormat::Dot
+                });
+            }
+            _ => return Ok(GraphFormat::EdgeList),
+        }
+    }
+    Ok(GraphFormat::EdgeList)
+}
+
+pub fn detect_format(path: &str) -> Result<GraphFormat, FormatError> {
+    match format_from_extension(path) {
+        Some(f) => Ok(f),
+        None => format_from_header(BufReader::new(File::open(path)?)),
+    }
+}
+
+// Reads any of the supported formats, picking the parser from the extension or header.
+// DOT files keep their own reader (utils::from_viz_dot).
+pub fn load_graph<T, E, ID>(path: &str) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default + FromStr,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let mut file = File::open(path)?;
+    let format = match format_from_extension(path) {
+        Some(f) => f,
+        None => {
+            let f = format_from_header(BufReader::new(&file))?;
+            file.seek(SeekFrom::Start(0))?;
+            f
+        }
+    };
+    let reader = BufReader::new(file);
+    match format {
+        GraphFormat::Dot => Err(FormatError::new("DOT files are read with from_viz_dot.")),
+        GraphFormat::EdgeList => read_edge_list(reader),
+        GraphFormat::AdjacencyList => read_adjacency_list(reader),
+        GraphFormat::MatrixMarket => read_matrix_market(reader, None),
+        GraphFormat::Gml => read_gml(reader),
+        GraphFormat::Pajek => read_pajek(reader),
+        GraphFormat::Gexf => read_gexf(reader).map(|(g, _)| g),
+    }
+}
@@ -0,0 +1,400 @@
+use crate::graph::Graph;
+use crate::utils::{edge_id_from, escape_entities, parse_token, unescape_entities, FormatError};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::str::FromStr;
+
+// GEXF 1.x, static or dynamic. Node data is kept in a node attribute titled 'data', edge data
+// in the edge 'weight'. Times (from 'start'/'end' attributes or <spell> elements) are kept
+// as written, since they may be numbers or dates depending on the file's timeformat. Edges
+// keep the id they are written with when it parses as an id, the others are numbered.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Spell {
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline<ID: Clone + Hash + Eq> {
+    pub nodes: HashMap<ID, Vec<Spell>>,
+    pub edges: HashMap<ID, Vec<Spell>>,
+}
+
+impl<ID: Clone + Hash + Eq> Timeline<ID> {
+    pub fn new() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            edges: HashMap::new(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+}
+
+pub type DynamicGraph<T, E, ID> = (Graph<T, E, ID>, Timeline<ID>);
+
+#[derive(Debug)]
+struct Tag {
+    name: String,
+    attrs: HashMap<String, String>,
+    closing: bool,
+    empty: bool,
+    line: usize,
+}
+
+// a minimal scanner: yields the element tags and skips text, comments and declarations.
+fn scan(text: &str) -> Result<Vec<Tag>, FormatError> {
+    let mut tags: Vec<Tag> = Vec::new();
+    let mut rest = text;
+    let mut line = 1;
+
+    while let Some(open) = rest.find('<') {
+        line += rest[..open].matches('\n').count();
+        rest = &rest[open..];
+
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<?") {
+            "?>"
+        } else {
+            ">"
+        };
+        let close = rest
+            .find(terminator)
+            .ok_or_else(|| FormatError::at_line(line, "unterminated tag."))?;
+        let body = &rest[1..close];
+        let start_line = line;
+        line += body.matches('\n').count();
+        rest = &rest[close + terminator.len()..];
+
+        if body.starts_with('!') || body.starts_with('?') {
+            continue;
+        }
+        let closing = body.starts_with('/');
+        let empty = body.ends_with('/');
+        let body = body.trim_start_matches('/').trim_end_matches('/');
+        let name_end = body.find(char::is_whitespace).unwrap_or(body.len());
+        tags.push(Tag {
+            name: body[..name_end].to_string(),
+            attrs: attributes(&body[name_end..], start_line)?,
+            closing,
+            empty,
+            line: start_line,
+        });
+    }
+    Ok(tags)
+}
+
+fn attributes(s: &str, line: usize) -> Result<HashMap<String, String>, FormatError> {
+    let mut attrs: HashMap<String, String> = HashMap::new();
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| FormatError::at_line(line, "malformed attribute."))?;
+        let key = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+        let quote = after
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| FormatError::at_line(line, "attribute value must be quoted."))?;
+        let end = after[1..]
+            .find(quote)
+            .ok_or_else(|| FormatError::at_line(line, "unterminated attribute value."))?;
+        attrs.insert(key, unescape_entities(&after[1..end + 1]));
+        rest = after[end + 2..].trim_start();
+    }
+    Ok(attrs)
+}
+
+fn spell_of(tag: &Tag) -> Option<Spell> {
+    let start = tag.attrs.get("start").cloned();
+    let end = tag.attrs.get("end").cloned();
+    if start.is_none() && end.is_none() {
+        return None;
+    }
+    Some(Spell { start, end })
+}
+
+// the node or edge currently being read.
+struct Pending<ID> {
+    id: ID,
+    ends: Option<(ID, ID)>,
+    data: Option<String>,
+    spells: Vec<Spell>,
+    line: usize,
+}
+
+pub fn read_gexf<T, E, ID, R>(reader: R) -> Result<DynamicGraph<T, E, ID>, FormatError>
+where
+    T: Default + FromStr,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: BufRead,
+{
+    let mut reader = reader;
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let tags = scan(&text)?;
+    if !tags.iter().any(|t| t.name == "gexf") {
+        return Err(FormatError::new("missing <gexf> element."));
+    }
+
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let mut timeline: Timeline<ID> = Timeline::new();
+    let mut class = String::new();
+    let mut data_attr: Option<String> = None;
+    let mut pending: Option<Pending<ID>> = None;
+
+    for tag in tags.iter() {
+        match (&tag.name[..], tag.closing) {
+            ("attributes", false) => {
+                class = tag.attrs.get("class").cloned().unwrap_or_default();
+            }
+            ("attribute", false) if class == "node" => {
+                let id = tag.attrs.get("id");
+                if tag.attrs.get("title").map(|t| &t[..]) == Some("data")
+                    || id.map(|t| &t[..]) == Some("data")
+                {
+                    data_attr = id.cloned();
+                }
+            }
+            ("node", false) => {
+                let id = tag
+                    .attrs
+                    .get("id")
+                    .ok_or_else(|| FormatError::at_line(tag.line, "node without an id."))?;
+                pending = Some(Pending {
+                    id: parse_token(id, tag.line, "node id")?,
+                    ends: None,
+                    data: None,
+                    spells: spell_of(tag).into_iter().collect(),
+                    line: tag.line,
+                });
+            }
+            ("edge", false) => {
+                let (s, t) = match (tag.attrs.get("source"), tag.attrs.get("target")) {
+                    (Some(s), Some(t)) => (s, t),
+                    _ => {
+                        return Err(FormatError::at_line(
+                            tag.line,
+                            "edge needs source and target.",
+                        ))
+                    }
+                };
+                // edges without a usable id take the first free one from the edge count.
+                let id = match tag.attrs.get("id").and_then(|id| id.parse::<ID>().ok()) {
+                    Some(id) => id,
+                    None => {
+                        let mut ix = g.edges.len();
+                        while g.edges.contains_key(&edge_id_from(ix, tag.line)?) {
+                            ix += 1;
+                        }
+                        edge_id_from(ix, tag.line)?
+                    }
+                };
+                pending = Some(Pending {
+                    id,
+                    ends: Some((
+                        parse_token(s, tag.line, "node id")?,
+                        parse_token(t, tag.line, "node id")?,
+                    )),
+                    data: tag.attrs.get("weight").cloned(),
+                    spells: spell_of(tag).into_iter().collect(),
+                    line: tag.line,
+                });
+            }
+            ("attvalue", false) => {
+                if let Some(p) = pending.as_mut() {
+                    if p.ends.is_none()
+                        && p.data.is_none()
+                        && tag.attrs.get("for") == data_attr.as_ref()
+                    {
+                        p.data = tag.attrs.get("value").cloned();
+                    }
+                }
+            }
+            ("spell", false) => {
+                if let (Some(p), Some(s)) = (pending.as_mut(), spell_of(tag)) {
+                    p.spells.push(s);
+                }
+            }
+            _ => {}
+        }
+
+        let finished = (tag.name == "node" || tag.name == "edge") && (tag.closing || tag.empty);
+        if !finished {
+            continue;
+        }
+        if let Some(p) = pending.take() {
+            match p.ends {
+                None => {
+                    let data: T = match p.data {
+                        None => T::default(),
+                        Some(d) => parse_token(&d, p.line, "node data")?,
+                    };
+                    g.add_node(p.id, data);
+                    if !p.spells.is_empty() {
+                        timeline.nodes.insert(p.id, p.spells);
+                    }
+                }
+                Some((from, to)) => {
+                    let data: E = match p.data {
+                        None => E::default(),
+                        Some(w) => parse_token(&w, p.line, "edge weight")?,
+                    };
+                    if g.edges.contains_key(&p.id) {
+                        return Err(FormatError::at_line(p.line, "duplicate edge id."));
+                    }
+                    if g.add_edge(p.id, from, to, data).is_err() {
+                        return Err(FormatError::at_line(
+                            p.line,
+                            "edge refers to an unknown node.",
+                        ));
+                    }
+                    if !p.spells.is_empty() {
+                        timeline.edges.insert(p.id, p.spells);
+                    }
+                }
+            }
+        }
+    }
+    Ok((g, timeline))
+}
+
+pub fn load_gexf<T, E, ID>(path: &str) -> Result<DynamicGraph<T, E, ID>, FormatError>
+where
+    T: Default + FromStr,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    read_gexf(BufReader::new(File::open(path)?))
+}
+
+fn write_spells<W: Write>(
+    writer: &mut W,
+    spells: &[Spell],
+    indent: &str,
+) -> Result<(), FormatError> {
+    writeln!(writer, "{}<spells>", indent)?;
+    for s in spells.iter() {
+        write!(writer, "{}  <spell", indent)?;
+        if let Some(start) = &s.start {
+            write!(writer, " start=\"{}\"", escape_entities(start))?;
+        }
+        if let Some(end) = &s.end {
+            write!(writer, " end=\"{}\"", escape_entities(end))?;
+        }
+        writeln!(writer, "/>")?;
+    }
+    writeln!(writer, "{}</spells>", indent)?;
+    Ok(())
+}
+
+// Writes a dynamic graph when a non empty timeline is given.
+pub fn write_gexf<T, E, ID, W>(
+    g: &Graph<T, E, ID>,
+    timeline: Option<&Timeline<ID>>,
+    writer: W,
+) -> Result<(), FormatError>
+where
+    T: Display,
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+    W: Write,
+{
+    let timeline = timeline.filter(|t| !t.is_empty());
+    let mode = if timeline.is_some() {
+        "dynamic"
+    } else {
+        "static"
+    };
+    let no_spells: Vec<Spell> = Vec::new();
+
+    let mut writer = writer;
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        writer,
+        "<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">"
+    )?;
+    writeln!(
+        writer,
+        "  <graph mode=\"{}\" defaultedgetype=\"undirected\">",
+        mode
+    )?;
+    writeln!(writer, "    <attributes class=\"node\">")?;
+    writeln!(
+        writer,
+        "      <attribute id=\"data\" title=\"data\" type=\"string\"/>"
+    )?;
+    writeln!(writer, "    </attributes>")?;
+
+    writeln!(writer, "    <nodes>")?;
+    for node_key in g.nodes.keys().sorted() {
+        let node = g.nodes.get(node_key).unwrap();
+        let id = escape_entities(&node.id.to_string());
+        writeln!(writer, "      <node id=\"{}\" label=\"{}\">", id, id)?;
+        writeln!(writer, "        <attvalues>")?;
+        writeln!(
+            writer,
+            "          <attvalue for=\"data\" value=\"{}\"/>",
+            escape_entities(&node.data.to_string())
+        )?;
+        writeln!(writer, "        </attvalues>")?;
+        let spells = timeline
+            .and_then(|t| t.nodes.get(node_key))
+            .unwrap_or(&no_spells);
+        if !spells.is_empty() {
+            write_spells(&mut writer, spells, "        ")?;
+        }
+        writeln!(writer, "      </node>")?;
+    }
+    writeln!(writer, "    </nodes>")?;
+
+    writeln!(writer, "    <edges>")?;
+    for edge_key in g.edges.keys().sorted() {
+        let edge = g.edges.get(edge_key).unwrap();
+        write!(
+            writer,
+            "      <edge id=\"{}\" source=\"{}\" target=\"{}\" weight=\"{}\"",
+            escape_entities(&edge.id.to_string()),
+            escape_entities(&edge.left.to_string()),
+            escape_entities(&edge.right.to_string()),
+            escape_entities(&edge.data.to_string())
+        )?;
+        let spells = timeline
+            .and_then(|t| t.edges.get(edge_key))
+            .unwrap_or(&no_spells);
+        if spells.is_empty() {
+            writeln!(writer, "/>")?;
+        } else {
+            writeln!(writer, ">")?;
+            write_spells(&mut writer, spells, "        ")?;
+            writeln!(writer, "      </edge>")?;
+        }
+    }
+    writeln!(writer, "    </edges>")?;
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</gexf>")?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn save_gexf<T, E, ID>(
+    g: &Graph<T, E, ID>,
+    timeline: Option<&Timeline<ID>>,
+    path: &str,
+) -> Result<(), FormatError>
+where
+    T: Display,
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+{
+    write_gexf(g, timeline, BufWriter::new(File::create(path)?))
+}
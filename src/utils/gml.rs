@@ -0,0 +1,253 @@
+use crate::graph::Graph;
+use crate::utils::{edge_id_from, escape_entities, parse_token, unescape_entities, FormatError};
+use itertools::Itertools;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::str::FromStr;
+
+// GML as written by networkx, igraph and yEd:
+//
+//     graph [
+//       node [ id 1 data "0" ]
+//       edge [ source 1 target 2 weight 3 ]
+//     ]
+//
+// Node data is kept in a 'data' key and edge data in 'weight' (or 'value').
+#[derive(Debug)]
+enum Value {
+    Atom(String),
+    Str(String),
+    List(Vec<Entry>),
+}
+
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    value: Value,
+    line: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+    Str(String),
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, FormatError> {
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '[' => tokens.push((Token::Open, line)),
+            ']' => tokens.push((Token::Close, line)),
+            '#' => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '"' => {
+                let start = line;
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err(FormatError::at_line(start, "unterminated string.")),
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            s.push(c);
+                        }
+                    }
+                }
+                tokens.push((Token::Str(unescape_entities(&s)), start));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut s = c.to_string();
+                while let Some(n) = chars.peek() {
+                    if n.is_whitespace() || *n == '[' || *n == ']' || *n == '"' {
+                        break;
+                    }
+                    s.push(*n);
+                    chars.next();
+                }
+                tokens.push((Token::Word(s), line));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_list(
+    tokens: &mut impl Iterator<Item = (Token, usize)>,
+    nested: bool,
+) -> Result<Vec<Entry>, FormatError> {
+    let mut entries: Vec<Entry> = Vec::new();
+    loop {
+        let (key, line) = match tokens.next() {
+            None if nested => return Err(FormatError::new("unexpected end of file, missing ']'.")),
+            None => return Ok(entries),
+            Some((Token::Close, _)) if nested => return Ok(entries),
+            Some((Token::Word(k), line)) => (k, line),
+            Some((_, line)) => return Err(FormatError::at_line(line, "expected a key.")),
+        };
+        let value = match tokens.next() {
+            None => return Err(FormatError::at_line(line, "key without a value.")),
+            Some((Token::Open, _)) => Value::List(parse_list(tokens, true)?),
+            Some((Token::Word(w), _)) => Value::Atom(w),
+            Some((Token::Str(s), _)) => Value::Str(s),
+            Some((Token::Close, line)) => {
+                return Err(FormatError::at_line(line, "unexpected ']'."))
+            }
+        };
+        entries.push(Entry { key, value, line });
+    }
+}
+
+fn scalar<'a>(entries: &'a [Entry], key: &str) -> Option<&'a str> {
+    entries
+        .iter()
+        .find(|e| e.key == key)
+        .and_then(|e| match &e.value {
+            Value::Atom(s) | Value::Str(s) => Some(s.as_str()),
+            Value::List(_) => None,
+        })
+}
+
+pub fn read_gml<T, E, ID, R>(reader: R) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default + FromStr,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: BufRead,
+{
+    let mut reader = reader;
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut tokens = tokenize(&text)?.into_iter();
+    let top = parse_list(&mut tokens, false)?;
+
+    let graph = top
+        .iter()
+        .find_map(|e| match (&e.key[..], &e.value) {
+            ("graph", Value::List(l)) => Some(l),
+            _ => None,
+        })
+        .ok_or_else(|| FormatError::new("no 'graph [ ... ]' block."))?;
+
+    let mut g: Graph<T, E, ID> = Graph::new();
+    for entry in graph.iter() {
+        let fields = match &entry.value {
+            Value::List(l) => l,
+            _ => continue,
+        };
+        match &entry.key[..] {
+            "node" => {
+                let id = scalar(fields, "id")
+                    .ok_or_else(|| FormatError::at_line(entry.line, "node without an id."))?;
+                let id: ID = parse_token(id, entry.line, "node id")?;
+                let data: T = match scalar(fields, "data") {
+                    None => T::default(),
+                    Some(d) => parse_token(d, entry.line, "node data")?,
+                };
+                g.add_node(id, data);
+            }
+            "edge" => {
+                let (s, t) = match (scalar(fields, "source"), scalar(fields, "target")) {
+                    (Some(s), Some(t)) => (s, t),
+                    _ => {
+                        return Err(FormatError::at_line(
+                            entry.line,
+                            "edge needs source and target.",
+                        ))
+                    }
+                };
+                let from: ID = parse_token(s, entry.line, "node id")?;
+                let to: ID = parse_token(t, entry.line, "node id")?;
+                let data: E = match scalar(fields, "weight").or_else(|| scalar(fields, "value")) {
+                    None => E::default(),
+                    Some(w) => parse_token(w, entry.line, "edge weight")?,
+                };
+                let edge_id = edge_id_from(g.edges.len(), entry.line)?;
+                if g.add_edge(edge_id, from, to, data).is_err() {
+                    return Err(FormatError::at_line(
+                        entry.line,
+                        "edge refers to an unknown node.",
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(g)
+}
+
+pub fn load_gml<T, E, ID>(path: &str) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default + FromStr,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    read_gml(BufReader::new(File::open(path)?))
+}
+
+pub fn write_gml<T, E, ID, W>(g: &Graph<T, E, ID>, writer: W) -> Result<(), FormatError>
+where
+    T: Display,
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+    W: Write,
+{
+    let mut writer = writer;
+    writeln!(writer, "graph [")?;
+    writeln!(writer, "  directed 0")?;
+    for node_key in g.nodes.keys().sorted() {
+        let node = g.nodes.get(node_key).unwrap();
+        writeln!(writer, "  node [")?;
+        writeln!(writer, "    id {}", node.id)?;
+        writeln!(
+            writer,
+            "    label \"{}\"",
+            escape_entities(&node.id.to_string())
+        )?;
+        writeln!(writer, "    data {}", gml_value(&node.data.to_string()))?;
+        writeln!(writer, "  ]")?;
+    }
+    for edge_key in g.edges.keys().sorted() {
+        let edge = g.edges.get(edge_key).unwrap();
+        writeln!(writer, "  edge [")?;
+        writeln!(writer, "    source {}", edge.left)?;
+        writeln!(writer, "    target {}", edge.right)?;
+        writeln!(writer, "    weight {}", gml_value(&edge.data.to_string()))?;
+        writeln!(writer, "  ]")?;
+    }
+    writeln!(writer, "]")?;
+    writer.flush()?;
+    Ok(())
+}
+
+//numbers are written bare, anything else as a quoted string.
+fn gml_value(s: &str) -> String {
+    if s.parse::<f64>().is_ok() {
+        s.to_string()
+    } else {
+        format!("\"{}\"", escape_entities(s))
+    }
+}
+
+pub fn save_gml<T, E, ID>(g: &Graph<T, E, ID>, path: &str) -> Result<(), FormatError>
+where
+    T: Display,
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+{
+    write_gml(g, BufWriter::new(File::create(path)?))
+}
@@ -0,0 +1,296 @@
+use crate::graph::{Graph, Weighted};
+use crate::utils::{edge_id_from, parse_token, FormatError};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::str::FromStr;
+
+// Maps node ids to matrix rows (and back).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdOrder<ID: Clone + Hash + Eq> {
+    pub ids: Vec<ID>,
+    index: HashMap<ID, usize>,
+}
+
+impl<ID: Copy + Clone + Hash + Eq> IdOrder<ID> {
+    pub fn from_ids(ids: Vec<ID>) -> Self {
+        let index = ids.iter().enumerate().map(|(ix, id)| (*id, ix)).collect();
+        Self { ids, index }
+    }
+    pub fn from_graph<T, E>(g: &Graph<T, E, ID>) -> Self
+    where
+        ID: Ord,
+    {
+        Self::from_ids(g.nodes.keys().copied().sorted().collect())
+    }
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+    pub fn index_of(&self, id: &ID) -> Option<usize> {
+        self.index.get(id).copied()
+    }
+    pub fn id_at(&self, row: usize) -> Option<ID> {
+        self.ids.get(row).copied()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdjacencyMatrix<ID: Clone + Hash + Eq> {
+    pub order: IdOrder<ID>,
+    pub values: Vec<Vec<i32>>,
+}
+
+impl<ID: Copy + Clone + Hash + Eq> AdjacencyMatrix<ID> {
+    pub fn get(&self, a: &ID, b: &ID) -> Option<i32> {
+        let r = self.order.index_of(a)?;
+        let c = self.order.index_of(b)?;
+        Some(self.values[r][c])
+    }
+}
+
+// Rows follow the sorted node ids. The graph is undirected so the matrix is symmetric,
+// parallel edges have their weights summed.
+pub fn to_adjacency_matrix<T, E: Weighted, ID: Copy + Clone + Hash + Eq + Ord>(
+    g: &Graph<T, E, ID>,
+) -> AdjacencyMatrix<ID> {
+    let order = IdOrder::from_graph(g);
+    let n = order.len();
+    let mut values = vec![vec![0; n]; n];
+    for edge in g.edges.values() {
+        let r = order.index_of(&edge.left).unwrap();
+        let c = order.index_of(&edge.right).unwrap();
+        values[r][c] += edge.data.weight();
+        if r != c {
+            values[c][r] += edge.data.weight();
+        }
+    }
+    AdjacencyMatrix { order, values }
+}
+
+// Only the upper triangle is read, every non zero entry becomes an edge. Fails when there
+// are more edges than the id type can number.
+pub fn from_adjacency_matrix<T, E, ID>(
+    m: &AdjacencyMatrix<ID>,
+) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: From<i32>,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    for id in m.order.ids.iter() {
+        g.add_node(*id, T::default());
+    }
+    for (r, row) in m.values.iter().enumerate() {
+        for (c, w) in row.iter().enumerate().skip(r) {
+            if *w == 0 {
+                continue;
+            }
+            let edge_id = ID::try_from(g.edges.len())
+                .map_err(|_| FormatError::new("edge id does not fit the id type."))?;
+            let _ = g.add_edge(edge_id, m.order.ids[r], m.order.ids[c], E::from(*w));
+        }
+    }
+    Ok(g)
+}
+
+// Matrix Market coordinate format, written as a symmetric integer matrix (lower triangle).
+pub fn write_matrix_market<T, E, ID, W>(
+    g: &Graph<T, E, ID>,
+    order: &IdOrder<ID>,
+    writer: W,
+) -> Result<(), FormatError>
+where
+    E: Weighted,
+    ID: Copy + Clone + Hash + Eq,
+    W: Write,
+{
+    let mut entries: HashMap<(usize, usize), i32> = HashMap::new();
+    for edge in g.edges.values() {
+        let (r, c) = match (order.index_of(&edge.left), order.index_of(&edge.right)) {
+            (Some(r), Some(c)) => (r, c),
+            _ => return Err(FormatError::new("node id missing from the id order.")),
+        };
+        *entries.entry((r.max(c), r.min(c))).or_insert(0) += edge.data.weight();
+    }
+
+    let mut writer = writer;
+    writeln!(writer, "%%MatrixMarket matrix coordinate integer symmetric")?;
+    writeln!(writer, "{} {} {}", order.len(), order.len(), entries.len())?;
+    for ((r, c), w) in entries.iter().sorted() {
+        writeln!(writer, "{} {} {}", r + 1, c + 1, w)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn save_matrix_market<T, E, ID>(
+    g: &Graph<T, E, ID>,
+    order: &IdOrder<ID>,
+    path: &str,
+) -> Result<(), FormatError>
+where
+    E: Weighted,
+    ID: Copy + Clone + Hash + Eq,
+{
+    write_matrix_market(g, order, BufWriter::new(File::create(path)?))
+}
+
+// Reads a coordinate Matrix Market file. Rows are mapped back through 'order' when given,
+// otherwise the node ids are the 1 based matrix indices. 'pattern' matrices get default edge data.
+pub fn read_matrix_market<T, E, ID, R>(
+    reader: R,
+    order: Option<&IdOrder<ID>>,
+) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: Default + FromStr,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: BufRead,
+{
+    let mut lines = reader.lines().enumerate().map(|(ix, l)| (ix + 1, l));
+
+    let (line_no, header) = match lines.next() {
+        Some((ix, l)) => (ix, l?),
+        None => return Err(FormatError::new("empty Matrix Market file.")),
+    };
+    let header = header.to_lowercase();
+    let fields: Vec<&str> = header.split_whitespace().collect();
+    if fields.len() != 5 || fields[0] != "%%matrixmarket" || fields[1] != "matrix" {
+        return Err(FormatError::at_line(
+            line_no,
+            "missing %%MatrixMarket header.",
+        ));
+    }
+    if fields[2] != "coordinate" {
+        return Err(FormatError::at_line(
+            line_no,
+            "only coordinate matrices are supported.",
+        ));
+    }
+    let pattern = match fields[3] {
+        "pattern" => true,
+        "integer" | "real" => false,
+        _ => return Err(FormatError::at_line(line_no, "unsupported field type.")),
+    };
+    let symmetric = match fields[4] {
+        "symmetric" => true,
+        "general" => false,
+        _ => return Err(FormatError::at_line(line_no, "unsupported symmetry.")),
+    };
+
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    let mut size: Option<(usize, usize)> = None;
+    let mut count = 0;
+
+    for (line_no, line) in lines {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        let (rows, nnz) = match size {
+            Some(s) => s,
+            None => {
+                if tokens.len() != 3 {
+                    return Err(FormatError::at_line(
+                        line_no,
+                        "expected 'rows cols entries'.",
+                    ));
+                }
+                let rows: usize = parse_token(tokens[0], line_no, "row count")?;
+                let cols: usize = parse_token(tokens[1], line_no, "column count")?;
+                let nnz: usize = parse_token(tokens[2], line_no, "entry count")?;
+                if rows != cols {
+                    return Err(FormatError::at_line(
+                        line_no,
+                        "adjacency matrix must be square.",
+                    ));
+                }
+                if let Some(o) = order {
+                    if o.len() != rows {
+                        return Err(FormatError::at_line(
+                            line_no,
+                            "id order does not match size.",
+                        ));
+                    }
+                }
+                for row in 0..rows {
+                    g.add_node(node_id(order, row, line_no)?, T::default());
+                }
+                size = Some((rows, nnz));
+                continue;
+            }
+        };
+
+        if tokens.len() != if pattern { 2 } else { 3 } {
+            return Err(FormatError::at_line(
+                line_no,
+                "wrong number of values in entry.",
+            ));
+        }
+        let r: usize = parse_token(tokens[0], line_no, "row index")?;
+        let c: usize = parse_token(tokens[1], line_no, "column index")?;
+        if r == 0 || c == 0 || r > rows || c > rows {
+            return Err(FormatError::at_line(line_no, "index out of range."));
+        }
+        let data: E = if pattern {
+            E::default()
+        } else {
+            parse_token(tokens[2], line_no, "value")?
+        };
+        count += 1;
+        if count > nnz {
+            return Err(FormatError::at_line(line_no, "more entries than declared."));
+        }
+
+        // a general matrix lists both (r, c) and (c, r) for an undirected edge.
+        if !symmetric && !seen.insert((r.min(c), r.max(c))) {
+            continue;
+        }
+        let from = node_id(order, r - 1, line_no)?;
+        let to = node_id(order, c - 1, line_no)?;
+        let edge_id = edge_id_from(g.edges.len(), line_no)?;
+        let _ = g.add_edge(edge_id, from, to, data);
+    }
+
+    match size {
+        None => Err(FormatError::new("missing size line.")),
+        Some((_, nnz)) if count < nnz => Err(FormatError::new("fewer entries than declared.")),
+        Some(_) => Ok(g),
+    }
+}
+
+pub fn load_matrix_market<T, E, ID>(
+    path: &str,
+    order: Option<&IdOrder<ID>>,
+) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: Default + FromStr,
+    ID: TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    read_matrix_market(BufReader::new(File::open(path)?), order)
+}
+
+fn node_id<ID: TryFrom<usize> + Copy + Clone + Hash + Eq>(
+    order: Option<&IdOrder<ID>>,
+    row: usize,
+    line: usize,
+) -> Result<ID, FormatError> {
+    match order {
+        Some(o) => o
+            .id_at(row)
+            .ok_or_else(|| FormatError::at_line(line, "row not in id order.")),
+        None => ID::try_from(row + 1)
+            .map_err(|_| FormatError::at_line(line, "node id does not fit the id type.")),
+    }
+}
@@ -0,0 +1,267 @@
+use crate::graph::Graph;
+use crate::utils::{edge_id_from, parse_token, FormatError};
+use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::str::FromStr;
+
+// Pajek .net files:
+//
+//     *Vertices 3
+//     1 "A"
+//     2 "B"
+//     *Edges
+//     1 2 5
+//     *Arcslist
+//     3 1 2
+//
+// Vertices are numbered from 1 and `*Vertices N` declares 1..N, listed or not. When every
+// vertex has a label that parses as an id and no two are equal, as write_pajek leaves them,
+// the labels are the node ids; otherwise the numbers are and the labels are skipped. Arcs are
+// read as undirected edges, an arc listed both ways is added once.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Section {
+    None,
+    Vertices,
+    Edges,
+    Arcs,
+    EdgesList,
+    ArcsList,
+}
+
+// splits a line into tokens, keeping quoted labels together.
+fn tokens(line: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut s = String::new();
+        if c == '"' {
+            for n in chars.by_ref() {
+                if n == '"' {
+                    break;
+                }
+                s.push(n);
+            }
+        } else {
+            s.push(c);
+            while let Some(n) = chars.peek() {
+                if n.is_whitespace() {
+                    break;
+                }
+                s.push(*n);
+                chars.next();
+            }
+        }
+        out.push(s);
+    }
+    out
+}
+
+pub fn read_pajek<T, E, ID, R>(reader: R) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+    R: BufRead,
+{
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let mut arcs: HashSet<(ID, ID)> = HashSet::new();
+    let mut section = Section::None;
+    // vertex number to its label and line, until the first edge settles the ids.
+    let mut vertices: BTreeMap<usize, (Option<String>, usize)> = BTreeMap::new();
+    let mut declared: Option<usize> = None;
+    let mut ids: Option<HashMap<usize, ID>> = None;
+
+    for (ix, line) in reader.lines().enumerate() {
+        let line_no = ix + 1;
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+
+        if line.starts_with('*') {
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap().to_lowercase();
+            section = match &keyword[..] {
+                "*vertices" => Section::Vertices,
+                "*edges" => Section::Edges,
+                "*arcs" => Section::Arcs,
+                "*edgeslist" => Section::EdgesList,
+                "*arcslist" => Section::ArcsList,
+                "*network" => Section::None,
+                _ => return Err(FormatError::at_line(line_no, "unknown section.")),
+            };
+            if let (Section::Vertices, Some(count)) = (section, words.next()) {
+                let count: usize = parse_token(count, line_no, "vertex count")?;
+                vertices.extend((1..=count).map(|n| (n, (None, line_no))));
+                declared = Some(count);
+            }
+            continue;
+        }
+
+        let tokens = tokens(line);
+        match section {
+            Section::None => {
+                return Err(FormatError::at_line(line_no, "data outside of a section."));
+            }
+            Section::Vertices => {
+                let number = vertex_number(&tokens[0], declared, line_no)?;
+                vertices.insert(number, (tokens.get(1).cloned(), line_no));
+                continue;
+            }
+            _ if ids.is_none() => ids = Some(vertex_ids(&mut g, &vertices)?),
+            _ => {}
+        }
+        let ids = ids.as_mut().unwrap();
+        let mut lookup = |number: &str| vertex(&mut g, ids, number, declared, line_no);
+
+        let pairs: Vec<(ID, ID, E)> = match section {
+            Section::Edges | Section::Arcs => {
+                if tokens.len() < 2 {
+                    return Err(FormatError::at_line(
+                        line_no,
+                        "expected a pair of vertices.",
+                    ));
+                }
+                let data: E = match tokens.get(2) {
+                    None => E::default(),
+                    Some(w) => parse_token(w, line_no, "edge weight")?,
+                };
+                vec![(lookup(&tokens[0])?, lookup(&tokens[1])?, data)]
+            }
+            _ => {
+                let from = lookup(&tokens[0])?;
+                let mut pairs = Vec::new();
+                for t in tokens.iter().skip(1) {
+                    pairs.push((from, lookup(t)?, E::default()));
+                }
+                pairs
+            }
+        };
+
+        let directed = section == Section::Arcs || section == Section::ArcsList;
+        for (from, to, data) in pairs {
+            if directed {
+                if arcs.contains(&(to, from)) {
+                    continue;
+                }
+                arcs.insert((from, to));
+            }
+            let edge_id = edge_id_from(g.edges.len(), line_no)?;
+            let _ = g.add_edge(edge_id, from, to, data);
+        }
+    }
+    if ids.is_none() {
+        vertex_ids(&mut g, &vertices)?;
+    }
+    Ok(g)
+}
+
+fn vertex_number(token: &str, declared: Option<usize>, line: usize) -> Result<usize, FormatError> {
+    let number: usize = parse_token(token, line, "vertex")?;
+    match declared {
+        Some(count) if number == 0 || number > count => Err(FormatError::at_line(
+            line,
+            "vertex number outside of the declared vertices.",
+        )),
+        _ => Ok(number),
+    }
+}
+
+fn number_id<ID: TryFrom<usize>>(number: usize, line: usize) -> Result<ID, FormatError> {
+    ID::try_from(number)
+        .map_err(|_| FormatError::at_line(line, "vertex number does not fit the id type."))
+}
+
+// adds the listed vertices, with their labels as ids when all of them parse as distinct ids
+// and their numbers otherwise.
+fn vertex_ids<T: Default, E, ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq>(
+    g: &mut Graph<T, E, ID>,
+    vertices: &BTreeMap<usize, (Option<String>, usize)>,
+) -> Result<HashMap<usize, ID>, FormatError> {
+    let labels: Option<Vec<ID>> = vertices
+        .values()
+        .map(|(label, _)| label.as_ref()?.parse::<ID>().ok())
+        .collect();
+    let ids: Vec<ID> = match labels {
+        Some(ids) if ids.iter().collect::<HashSet<_>>().len() == ids.len() => ids,
+        _ => vertices
+            .iter()
+            .map(|(number, (_, line))| number_id(*number, *line))
+            .collect::<Result<_, _>>()?,
+    };
+    for id in ids.iter() {
+        g.add_node(*id, T::default());
+    }
+    Ok(vertices.keys().copied().zip(ids).collect())
+}
+
+// a vertex first seen in the edges, without a vertex count, takes its number as id.
+fn vertex<T: Default, E, ID: TryFrom<usize> + Copy + Clone + Hash + Eq>(
+    g: &mut Graph<T, E, ID>,
+    ids: &mut HashMap<usize, ID>,
+    token: &str,
+    declared: Option<usize>,
+    line: usize,
+) -> Result<ID, FormatError> {
+    let number = vertex_number(token, declared, line)?;
+    if let Some(id) = ids.get(&number) {
+        return Ok(*id);
+    }
+    let id = number_id(number, line)?;
+    g.add_node(id, T::default());
+    ids.insert(number, id);
+    Ok(id)
+}
+
+pub fn load_pajek<T, E, ID>(path: &str) -> Result<Graph<T, E, ID>, FormatError>
+where
+    T: Default,
+    E: Default + FromStr,
+    ID: FromStr + TryFrom<usize> + Copy + Clone + Hash + Eq,
+{
+    read_pajek(BufReader::new(File::open(path)?))
+}
+
+pub fn write_pajek<T, E, ID, W>(g: &Graph<T, E, ID>, writer: W) -> Result<(), FormatError>
+where
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+    W: Write,
+{
+    let mut writer = writer;
+    let mut numbers: HashMap<ID, usize> = HashMap::new();
+    writeln!(writer, "*Vertices {}", g.nodes.len())?;
+    for (ix, node_key) in g.nodes.keys().sorted().enumerate() {
+        numbers.insert(*node_key, ix + 1);
+        writeln!(writer, "{} \"{}\"", ix + 1, node_key)?;
+    }
+    writeln!(writer, "*Edges")?;
+    for edge_key in g.edges.keys().sorted() {
+        let edge = g.edges.get(edge_key).unwrap();
+        writeln!(
+            writer,
+            "{} {} {}",
+            numbers.get(&edge.left).unwrap(),
+            numbers.get(&edge.right).unwrap(),
+            edge.data
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn save_pajek<T, E, ID>(g: &Graph<T, E, ID>, path: &str) -> Result<(), FormatError>
+where
+    E: Display,
+    ID: Display + Copy + Clone + Hash + Eq + Ord,
+{
+    write_pajek(g, BufWriter::new(File::create(path)?))
+}
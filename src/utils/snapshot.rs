@@ -0,0 +1,393 @@
+use crate::geometry::Vect;
+use crate::graph::Graph;
+use crate::utils::FormatError;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufWriter, Read, Write};
+
+// Binary graph snapshots. Layout (all integers little endian):
+//
+//     magic     8 bytes  "RGSNAPSH"
+//     major     u16      readers refuse files with a newer major version
+//     minor     u16      newer minors only add sections, which older readers skip
+//     flags     u32      bit 0: positions section present
+//     types     u8 length and that many bytes, the codecs of node data, edge data and
+//               ids separated by spaces, e.g. "i32 string u64"
+//     length    u64      payload length
+//     checksum  u32      CRC-32 of the payload
+//     payload   sections of (tag u8, length u64, bytes)
+//
+// Sections are nodes (id, data), edges (id, left, right, data) and positions (id, x, y, z).
+// Reading a snapshot as a graph of other types fails on the types field.
+const MAGIC: &[u8; 8] = b"RGSNAPSH";
+const MAJOR: u16 = 1;
+const MINOR: u16 = 0;
+const HEADER_LEN: usize = 8 + 2 + 2 + 4 + 1 + 8 + 4;
+const FLAG_POSITIONS: u32 = 1;
+
+const SECTION_NODES: u8 = 1;
+const SECTION_EDGES: u8 = 2;
+const SECTION_POSITIONS: u8 = 3;
+
+#[derive(Debug)]
+pub struct Snapshot<T, E, ID: Clone + Hash + Eq> {
+    pub graph: Graph<T, E, ID>,
+    pub positions: Option<HashMap<ID, Vect>>,
+}
+
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], FormatError> {
+        if self.data.len() - self.pos < n {
+            return Err(FormatError::new("snapshot is truncated."));
+        }
+        let s = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(s)
+    }
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], FormatError> {
+        let mut a = [0; N];
+        a.copy_from_slice(self.take(N)?);
+        Ok(a)
+    }
+    fn is_empty(&self) -> bool {
+        self.pos == self.data.len()
+    }
+}
+
+// Values that can be stored in a snapshot; implemented for the primitive types used as
+// node data, edge data and ids. Types with the same encoding share a codec name.
+pub trait SnapshotValue: Sized {
+    const CODEC: &'static str;
+    fn encode(&self, out: &mut Vec<u8>);
+    fn decode(input: &mut ByteReader) -> Result<Self, FormatError>;
+}
+
+macro_rules! snapshot_number {
+    ($($t:ty),*) => {
+        $(
+            impl SnapshotValue for $t {
+                const CODEC: &'static str = stringify!($t);
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+                fn decode(input: &mut ByteReader) -> Result<Self, FormatError> {
+                    Ok(<$t>::from_le_bytes(input.take_array()?))
+                }
+            }
+        )*
+    };
+}
+
+snapshot_number!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+impl SnapshotValue for usize {
+    const CODEC: &'static str = u64::CODEC;
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+    fn decode(input: &mut ByteReader) -> Result<Self, FormatError> {
+        usize::try_from(u64::decode(input)?)
+            .map_err(|_| FormatError::new("value does not fit usize."))
+    }
+}
+
+impl SnapshotValue for bool {
+    const CODEC: &'static str = "bool";
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+    fn decode(input: &mut ByteReader) -> Result<Self, FormatError> {
+        Ok(u8::decode(input)? != 0)
+    }
+}
+
+impl SnapshotValue for char {
+    const CODEC: &'static str = "char";
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode(out);
+    }
+    fn decode(input: &mut ByteReader) -> Result<Self, FormatError> {
+        char::from_u32(u32::decode(input)?).ok_or_else(|| FormatError::new("invalid char."))
+    }
+}
+
+impl SnapshotValue for String {
+    const CODEC: &'static str = "string";
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+    fn decode(input: &mut ByteReader) -> Result<Self, FormatError> {
+        let len = usize::decode(input)?;
+        String::from_utf8(input.take(len)?.to_vec())
+            .map_err(|_| FormatError::new("invalid utf-8 string."))
+    }
+}
+
+impl SnapshotValue for Vect {
+    const CODEC: &'static str = "vect";
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.x.encode(out);
+        self.y.encode(out);
+        self.z.encode(out);
+    }
+    fn decode(input: &mut ByteReader) -> Result<Self, FormatError> {
+        Ok(Vect::new(
+            f64::decode(input)?,
+            f64::decode(input)?,
+            f64::decode(input)?,
+        ))
+    }
+}
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut c = 0xFFFF_FFFFu32;
+    for b in data {
+        c = CRC_TABLE[((c ^ *b as u32) & 0xFF) as usize] ^ (c >> 8);
+    }
+    c ^ 0xFFFF_FFFF
+}
+
+fn codecs<T: SnapshotValue, E: SnapshotValue, ID: SnapshotValue>() -> String {
+    format!("{} {} {}", T::CODEC, E::CODEC, ID::CODEC)
+}
+
+fn section(out: &mut Vec<u8>, tag: u8, body: &[u8]) {
+    out.push(tag);
+    body.len().encode(out);
+    out.extend_from_slice(body);
+}
+
+pub fn encode_snapshot<T, E, ID>(
+    g: &Graph<T, E, ID>,
+    positions: Option<&HashMap<ID, Vect>>,
+) -> Result<Vec<u8>, FormatError>
+where
+    T: SnapshotValue,
+    E: SnapshotValue,
+    ID: SnapshotValue + Copy + Clone + Hash + Eq + Ord,
+{
+    let mut payload: Vec<u8> = Vec::new();
+
+    let mut body: Vec<u8> = Vec::new();
+    g.nodes.len().encode(&mut body);
+    for node_key in g.nodes.keys().sorted() {
+        let node = g.nodes.get(node_key).unwrap();
+        node.id.encode(&mut body);
+        node.data.encode(&mut body);
+    }
+    section(&mut payload, SECTION_NODES, &body);
+
+    let mut body: Vec<u8> = Vec::new();
+    g.edges.len().encode(&mut body);
+    for edge_key in g.edges.keys().sorted() {
+        let edge = g.edges.get(edge_key).unwrap();
+        edge.id.encode(&mut body);
+        edge.left.encode(&mut body);
+        edge.right.encode(&mut body);
+        edge.data.encode(&mut body);
+    }
+    section(&mut payload, SECTION_EDGES, &body);
+
+    let mut flags = 0;
+    if let Some(p) = positions {
+        flags |= FLAG_POSITIONS;
+        let mut body: Vec<u8> = Vec::new();
+        p.len().encode(&mut body);
+        for id in p.keys().sorted() {
+            id.encode(&mut body);
+            p.get(id).unwrap().encode(&mut body);
+        }
+        section(&mut payload, SECTION_POSITIONS, &body);
+    }
+
+    let mut out: Vec<u8> = Vec::with_capacity(HEADER_LEN + payload.len());
+    out.extend_from_slice(MAGIC);
+    MAJOR.encode(&mut out);
+    MINOR.encode(&mut out);
+    flags.encode(&mut out);
+    let types = codecs::<T, E, ID>();
+    u8::try_from(types.len())
+        .map_err(|_| FormatError::new("type description is longer than 255 bytes."))?
+        .encode(&mut out);
+    out.extend_from_slice(types.as_bytes());
+    payload.len().encode(&mut out);
+    crc32(&payload).encode(&mut out);
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+// Works on any byte slice, e.g. a memory mapped file.
+pub fn decode_snapshot<T, E, ID>(bytes: &[u8]) -> Result<Snapshot<T, E, ID>, FormatError>
+where
+    T: SnapshotValue,
+    E: SnapshotValue,
+    ID: SnapshotValue + Copy + Clone + Hash + Eq,
+{
+    let mut header = ByteReader::new(bytes);
+    if bytes.len() < MAGIC.len() || header.take(MAGIC.len())? != MAGIC {
+        return Err(FormatError::new("not a graph snapshot."));
+    }
+    let major = u16::decode(&mut header)?;
+    let _minor = u16::decode(&mut header)?;
+    if major > MAJOR {
+        return Err(FormatError::new(&format!(
+            "snapshot version {} is newer than the supported version {}.",
+            major, MAJOR
+        )));
+    }
+    let flags = u32::decode(&mut header)?;
+    let len = u8::decode(&mut header)? as usize;
+    let written = String::from_utf8_lossy(header.take(len)?).to_string();
+    let expected = codecs::<T, E, ID>();
+    if written != expected {
+        return Err(FormatError::new(&format!(
+            "snapshot holds node data, edge data and ids of types '{}', not '{}'.",
+            written, expected
+        )));
+    }
+    let length = usize::decode(&mut header)?;
+    let checksum = u32::decode(&mut header)?;
+    let payload = header.take(length)?;
+    if !header.is_empty() {
+        return Err(FormatError::new("trailing bytes after snapshot payload."));
+    }
+    if crc32(payload) != checksum {
+        return Err(FormatError::new(
+            "snapshot checksum mismatch, the file is corrupt.",
+        ));
+    }
+
+    let mut g: Graph<T, E, ID> = Graph::new();
+    let mut positions: Option<HashMap<ID, Vect>> = None;
+    let mut input = ByteReader::new(payload);
+    while !input.is_empty() {
+        let tag = u8::decode(&mut input)?;
+        let len = usize::decode(&mut input)?;
+        let mut body = ByteReader::new(input.take(len)?);
+        match tag {
+            SECTION_NODES => {
+                for _ in 0..usize::decode(&mut body)? {
+                    let id = ID::decode(&mut body)?;
+                    g.add_node(id, T::decode(&mut body)?);
+                }
+            }
+            SECTION_EDGES => {
+                for _ in 0..usize::decode(&mut body)? {
+                    let id = ID::decode(&mut body)?;
+                    let left = ID::decode(&mut body)?;
+                    let right = ID::decode(&mut body)?;
+                    let data = E::decode(&mut body)?;
+                    g.add_edge(id, left, right, data)
+                        .map_err(|_| FormatError::new("edge refers to an unknown node."))?;
+                }
+            }
+            SECTION_POSITIONS => {
+                let mut p: HashMap<ID, Vect> = HashMap::new();
+                for _ in 0..usize::decode(&mut body)? {
+                    let id = ID::decode(&mut body)?;
+                    p.insert(id, Vect::decode(&mut body)?);
+                }
+                positions = Some(p);
+            }
+            // sections added by newer minor versions.
+            _ => continue,
+        }
+        if !body.is_empty() {
+            return Err(FormatError::new("snapshot section has trailing bytes."));
+        }
+    }
+    if flags & FLAG_POSITIONS != 0 && positions.is_none() {
+        return Err(FormatError::new(
+            "snapshot is missing its positions section.",
+        ));
+    }
+    Ok(Snapshot {
+        graph: g,
+        positions,
+    })
+}
+
+pub fn write_snapshot<T, E, ID, W>(
+    g: &Graph<T, E, ID>,
+    positions: Option<&HashMap<ID, Vect>>,
+    writer: W,
+) -> Result<(), FormatError>
+where
+    T: SnapshotValue,
+    E: SnapshotValue,
+    ID: SnapshotValue + Copy + Clone + Hash + Eq + Ord,
+    W: Write,
+{
+    let mut writer = writer;
+    writer.write_all(&encode_snapshot(g, positions)?)?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read_snapshot<T, E, ID, R>(reader: R) -> Result<Snapshot<T, E, ID>, FormatError>
+where
+    T: SnapshotValue,
+    E: SnapshotValue,
+    ID: SnapshotValue + Copy + Clone + Hash + Eq,
+    R: Read,
+{
+    let mut reader = reader;
+    let mut bytes: Vec<u8> = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    decode_snapshot(&bytes)
+}
+
+pub fn save_snapshot<T, E, ID>(
+    g: &Graph<T, E, ID>,
+    positions: Option<&HashMap<ID, Vect>>,
+    path: &str,
+) -> Result<(), FormatError>
+where
+    T: SnapshotValue,
+    E: SnapshotValue,
+    ID: SnapshotValue + Copy + Clone + Hash + Eq + Ord,
+{
+    write_snapshot(g, positions, BufWriter::new(File::create(path)?))
+}
+
+pub fn load_snapshot<T, E, ID>(path: &str) -> Result<Snapshot<T, E, ID>, FormatError>
+where
+    T: SnapshotValue,
+    E: SnapshotValue,
+    ID: SnapshotValue + Copy + Clone + Hash + Eq,
+{
+    read_snapshot(File::open(path)?)
+}
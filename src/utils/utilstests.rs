@@ -0,0 +1,409 @@
+use crate::geometry::Vect;
+use crate::graph::Graph;
+use crate::utils::adjlist::{read_adjacency_list, write_adjacency_list};
+use crate::utils::edgelist::{read_edge_list, write_edge_list};
+use crate::utils::formats::{format_from_extension, format_from_header, GraphFormat};
+use crate::utils::gexf::{read_gexf, write_gexf, Spell};
+use crate::utils::gml::{read_gml, write_gml};
+use crate::utils::matrix::{
+    from_adjacency_matrix, read_matrix_market, to_adjacency_matrix, write_matrix_market,
+    AdjacencyMatrix, IdOrder,
+};
+use crate::utils::pajek::{read_pajek, write_pajek};
+use crate::utils::snapshot::{
+    decode_snapshot, encode_snapshot, ByteReader, Snapshot, SnapshotValue,
+};
+use crate::utils::{from_viz_dot, save_viz_dot, write_viz_dot, FormatError};
+use std::collections::HashMap;
+use std::io::Cursor;
+
+#[test]
+fn edge_list_with_comments_and_weights() {
+    let text =
+        "# SNAP style header\n% KONECT style header\n\n1 2\n2\t3 7 1234567\n3 1 # trailing\n";
+    let g: Graph<i32, i32, i32> = read_edge_list(Cursor::new(text)).unwrap();
+    assert_eq!(3, g.nodes.len());
+    assert_eq!(3, g.edges.len());
+    assert!(g.connected(&1, &2));
+    assert!(g.connected(&3, &1));
+    assert!(g
+        .edges
+        .values()
+        .any(|e| e.left == 2 && e.right == 3 && e.data == 7));
+    assert!(g
+        .edges
+        .values()
+        .any(|e| e.left == 1 && e.right == 2 && e.data == 0));
+}
+
+#[test]
+fn edge_list_reports_line() {
+    let text = "1 2\n2 x\n";
+    let e = read_edge_list::<i32, i32, i32, _>(Cursor::new(text)).unwrap_err();
+    assert_eq!(Some(2), e.line);
+
+    let text = "1 2\n\n3\n";
+    let e = read_edge_list::<i32, i32, i32, _>(Cursor::new(text)).unwrap_err();
+    assert_eq!(Some(3), e.line);
+}
+
+#[test]
+fn edge_list_round_trip() {
+    let text = "1 2 5\n2 3 6\n";
+    let g: Graph<i32, i32, i32> = read_edge_list(Cursor::new(text)).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    write_edge_list(&g, &mut out, true).unwrap();
+    assert_eq!(text, String::from_utf8(out).unwrap());
+}
+
+#[test]
+fn adjacency_list_round_trip() {
+    let text = "1 2 3:4\n2 1 3\n5\n";
+    let g: Graph<i32, i32, i64> = read_adjacency_list(Cursor::new(text)).unwrap();
+    assert_eq!(4, g.nodes.len());
+    assert_eq!(3, g.edges.len());
+    assert!(g.neighbors(5).unwrap().is_empty());
+
+    let mut out: Vec<u8> = Vec::new();
+    write_adjacency_list(&g, &mut out, true).unwrap();
+    assert_eq!("1 2:0 3:4\n2 3:0\n3\n5\n", String::from_utf8(out).unwrap());
+}
+
+fn triangle() -> Graph<i32, i32, i32> {
+    let mut g: Graph<i32, i32, i32> = Graph::new();
+    for n in [10, 20, 30, 40] {
+        g.add_node(n, 0);
+    }
+    g.add_edge(1, 10, 20, 3).unwrap();
+    g.add_edge(2, 20, 30, 4).unwrap();
+    g.add_edge(3, 30, 10, 5).unwrap();
+    g
+}
+
+#[test]
+fn adjacency_matrix_round_trip() {
+    let g = triangle();
+    let m = to_adjacency_matrix(&g);
+    assert_eq!(vec![10, 20, 30, 40], m.order.ids);
+    assert_eq!(Some(2), m.order.index_of(&30));
+    assert_eq!(vec![0, 3, 5, 0], m.values[0]);
+    assert_eq!(Some(4), m.get(&30, &20));
+    assert_eq!(Some(0), m.get(&40, &10));
+
+    let g2: Graph<i32, i32, i32> = from_adjacency_matrix(&m).unwrap();
+    assert_eq!(4, g2.nodes.len());
+    assert_eq!(3, g2.edges.len());
+    assert_eq!(m, to_adjacency_matrix(&g2));
+
+    // 325 edges can not be numbered with u8 ids.
+    let ids: Vec<u8> = (0..=24).collect();
+    let full = AdjacencyMatrix {
+        values: vec![vec![1; ids.len()]; ids.len()],
+        order: IdOrder::from_ids(ids),
+    };
+    assert!(from_adjacency_matrix::<i32, i32, u8>(&full).is_err());
+}
+
+#[test]
+fn matrix_market_round_trip() {
+    let g = triangle();
+    let order = IdOrder::from_graph(&g);
+    let mut out: Vec<u8> = Vec::new();
+    write_matrix_market(&g, &order, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(
+        "%%MatrixMarket matrix coordinate integer symmetric\n4 4 3\n2 1 3\n3 1 5\n3 2 4\n",
+        text
+    );
+
+    let g2: Graph<i32, i32, i32> = read_matrix_market(Cursor::new(&text), Some(&order)).unwrap();
+    assert_eq!(to_adjacency_matrix(&g), to_adjacency_matrix(&g2));
+
+    let g3: Graph<i32, i32, i32> = read_matrix_market(Cursor::new(&text), None).unwrap();
+    assert!(g3.connected(&1, &3));
+    assert!(g3.nodes.contains_key(&4));
+}
+
+#[test]
+fn matrix_market_general_pattern() {
+    let text =
+        "%%MatrixMarket matrix coordinate pattern general\n% comment\n3 3 4\n1 2\n2 1\n2 3\n3 2\n";
+    let g: Graph<i32, i32, i32> = read_matrix_market(Cursor::new(text), None).unwrap();
+    assert_eq!(2, g.edges.len());
+
+    let text = "%%MatrixMarket matrix coordinate integer general\n2 2 1\n1 3 1\n";
+    let e = read_matrix_market::<i32, i32, i32, _>(Cursor::new(text), None).unwrap_err();
+    assert_eq!(Some(3), e.line);
+}
+
+#[test]
+fn gml_round_trip() {
+    let text = "Creator \"test\"\ngraph [\n  # comment\n  node [ id 1 label \"a\" data 7 ]\n  node [ id 2 ]\n  edge [ source 1 target 2 value 3 ]\n]\n";
+    let g: Graph<i32, i32, i32> = read_gml(Cursor::new(text)).unwrap();
+    assert_eq!(7, g.nodes.get(&1).unwrap().data);
+    assert_eq!(3, g.edges.get(&0).unwrap().data);
+
+    let mut out: Vec<u8> = Vec::new();
+    write_gml(&g, &mut out).unwrap();
+    let g2: Graph<i32, i32, i32> = read_gml(Cursor::new(out)).unwrap();
+    assert_eq!(to_adjacency_matrix(&g), to_adjacency_matrix(&g2));
+    assert_eq!(7, g2.nodes.get(&1).unwrap().data);
+
+    let e = read_gml::<i32, i32, i32, _>(Cursor::new(
+        "graph [\n node [ id 1 ]\n edge [ source 1 ]\n]",
+    ))
+    .unwrap_err();
+    assert_eq!(Some(3), e.line);
+}
+
+#[test]
+fn pajek_round_trip() {
+    let text = "*Vertices 3\n1 \"10\"\n2 \"20\" 0.1 0.2\n3\n*Edges\n1 2 4\n*Arcs\n2 3\n3 2\n*Edgeslist\n1 3\n";
+    let g: Graph<i32, i32, i32> = read_pajek(Cursor::new(text)).unwrap();
+    assert_eq!(3, g.nodes.len());
+    assert_eq!(3, g.edges.len());
+    assert!(g.connected(&1, &2));
+    assert!(g.connected(&2, &3));
+    assert!(g.connected(&1, &3));
+
+    // labels are names, not ids.
+    let named = "*Vertices 2\n1 \"Alice\"\n2 \"Bob Smith\"\n*Edges\n1 2\n";
+    let g3: Graph<i32, i32, i32> = read_pajek(Cursor::new(named)).unwrap();
+    assert!(g3.connected(&1, &2));
+
+    let mut out: Vec<u8> = Vec::new();
+    write_pajek(&g, &mut out).unwrap();
+    let g2: Graph<i32, i32, i32> = read_pajek(Cursor::new(out)).unwrap();
+    assert_eq!(to_adjacency_matrix(&g), to_adjacency_matrix(&g2));
+
+    // sparse ids come back through the labels, isolated nodes included.
+    let mut sparse: Graph<i32, i32, i32> = Graph::new();
+    for n in [5, 17, 300, 1000, -4] {
+        sparse.add_node(n, 0);
+    }
+    sparse.add_edge(0, 5, 300, 2).unwrap();
+    sparse.add_edge(1, 300, -4, 3).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    write_pajek(&sparse, &mut out).unwrap();
+    let g4: Graph<i32, i32, i32> = read_pajek(Cursor::new(out)).unwrap();
+    assert_eq!(to_adjacency_matrix(&sparse), to_adjacency_matrix(&g4));
+
+    // every declared vertex is a node, listed or not, and edges stay within them.
+    let unlisted = "*Vertices 4\n1 \"a\"\n*Edges\n1 2\n";
+    let g5: Graph<i32, i32, i32> = read_pajek(Cursor::new(unlisted)).unwrap();
+    assert_eq!(4, g5.nodes.len());
+    assert!(g5.connected(&1, &2));
+    let outside = "*Vertices 2\n*Edges\n1 3\n";
+    let e = read_pajek::<i32, i32, i32, _>(Cursor::new(outside)).unwrap_err();
+    assert_eq!(Some(3), e.line);
+}
+
+#[test]
+fn gexf_dynamic_round_trip() {
+    let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://gexf.net/1.3" version="1.3">
+  <!-- a comment -->
+  <graph mode="dynamic" defaultedgetype="undirected">
+    <attributes class="node">
+      <attribute id="0" title="data" type="integer"/>
+    </attributes>
+    <nodes>
+      <node id="1" label="one" start="2000">
+        <attvalues><attvalue for="0" value="5"/></attvalues>
+      </node>
+      <node id="2"/>
+    </nodes>
+    <edges>
+      <edge id="e" source="1" target="2" weight="3">
+        <spells>
+          <spell start="2001" end="2003"/>
+          <spell start="2005"/>
+        </spells>
+      </edge>
+    </edges>
+  </graph>
+</gexf>"#;
+    let (g, timeline) = read_gexf::<i32, i32, i32, _>(Cursor::new(text)).unwrap();
+    assert_eq!(5, g.nodes.get(&1).unwrap().data);
+    assert_eq!(3, g.edges.get(&0).unwrap().data);
+    assert_eq!(
+        Some("2000".to_string()),
+        timeline.nodes.get(&1).unwrap()[0].start
+    );
+    assert_eq!(
+        Spell {
+            start: Some("2005".to_string()),
+            end: None
+        },
+        timeline.edges.get(&0).unwrap()[1]
+    );
+
+    let mut out: Vec<u8> = Vec::new();
+    write_gexf(&g, Some(&timeline), &mut out).unwrap();
+    let (g2, timeline2) = read_gexf::<i32, i32, i32, _>(Cursor::new(out)).unwrap();
+    assert_eq!(to_adjacency_matrix(&g), to_adjacency_matrix(&g2));
+    assert_eq!(3, g2.edges.get(&0).unwrap().data);
+    assert_eq!(5, g2.nodes.get(&1).unwrap().data);
+    assert_eq!(timeline, timeline2);
+}
+
+#[test]
+fn gexf_keeps_edge_ids() {
+    let mut g: Graph<i32, i32, i32> = Graph::new();
+    for n in [1, 2, 3] {
+        g.add_node(n, 0);
+    }
+    g.add_edge(7, 1, 2, 4).unwrap();
+    g.add_edge(42, 2, 3, 5).unwrap();
+
+    let mut out: Vec<u8> = Vec::new();
+    write_gexf(&g, None, &mut out).unwrap();
+    let (g2, _) = read_gexf::<i32, i32, i32, _>(Cursor::new(out)).unwrap();
+    let mut ids: Vec<i32> = g2.edges.keys().copied().collect();
+    ids.sort();
+    assert_eq!(vec![7, 42], ids);
+    assert_eq!(5, g2.edges.get(&42).unwrap().data);
+
+    // missing and non numeric ids take the first free id, a repeated id is an error.
+    let text = |last: &str| {
+        format!(
+            r#"<gexf><graph><nodes><node id="1"/><node id="2"/><node id="3"/></nodes>
+<edges>
+<edge id="1" source="1" target="2"/>
+<edge source="2" target="3"/>
+<edge id="x" source="1" target="3"/>
+{}
+</edges></graph></gexf>"#,
+            last
+        )
+    };
+    let (g3, _) = read_gexf::<i32, i32, i32, _>(Cursor::new(text(""))).unwrap();
+    let mut ids: Vec<i32> = g3.edges.keys().copied().collect();
+    ids.sort();
+    assert_eq!(vec![1, 2, 3], ids);
+    for n in [1, 2, 3] {
+        assert_eq!(2, g3.degree(n).unwrap());
+    }
+    let last = r#"<edge id="3" source="2" target="1"/>"#;
+    let e = read_gexf::<i32, i32, i32, _>(Cursor::new(text(last))).unwrap_err();
+    assert_eq!(Some(6), e.line);
+}
+
+#[test]
+fn detect_formats() {
+    assert_eq!(Some(GraphFormat::Gexf), format_from_extension("a/b.GEXF"));
+    assert_eq!(Some(GraphFormat::Pajek), format_from_extension("b.net"));
+    assert_eq!(None, format_from_extension("b.txt"));
+
+    let detect = |s: &str| format_from_header(Cursor::new(s)).unwrap();
+    assert_eq!(
+        GraphFormat::MatrixMarket,
+        detect("%%MatrixMarket matrix coordinate real general\n")
+    );
+    assert_eq!(GraphFormat::Gexf, detect("<?xml version=\"1.0\"?>\n<gexf>"));
+    assert_eq!(GraphFormat::Pajek, detect("*Vertices 4\n"));
+    assert_eq!(GraphFormat::Gml, detect("# comment\ngraph [\n"));
+    assert_eq!(GraphFormat::Dot, detect("graph D {\n"));
+    assert_eq!(GraphFormat::Dot, detect("graph G\n{\n"));
+    assert_eq!(GraphFormat::Gml, detect("graph\n[\n"));
+    assert_eq!(GraphFormat::Dot, detect("digraph\n{\n"));
+    assert_eq!(
+        GraphFormat::EdgeList,
+        detect("# FromNodeId ToNodeId\n1 2\n")
+    );
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let g = triangle();
+    let mut positions: HashMap<i32, Vect> = HashMap::new();
+    positions.insert(10, Vect::new(1., 2., 3.));
+    positions.insert(20, Vect::new(-1., 0.5, 0.));
+
+    let bytes = encode_snapshot(&g, Some(&positions)).unwrap();
+    let s: Snapshot<i32, i32, i32> = decode_snapshot(&bytes).unwrap();
+    assert_eq!(g.nodes, s.graph.nodes);
+    assert_eq!(g.edges, s.graph.edges);
+    assert_eq!(Some(positions), s.positions);
+
+    let bytes = encode_snapshot(&g, None).unwrap();
+    let s: Snapshot<i32, i32, i32> = decode_snapshot(&bytes).unwrap();
+    assert_eq!(None, s.positions);
+}
+
+#[test]
+fn snapshot_rejects_bad_files() {
+    let bytes = encode_snapshot(&triangle(), None).unwrap();
+
+    let e = decode_snapshot::<i32, i32, i32>(&bytes[..bytes.len() - 3]).unwrap_err();
+    assert!(e.to_string().contains("truncated"));
+
+    let mut corrupt = bytes.clone();
+    let last = corrupt.len() - 1;
+    corrupt[last] ^= 0xFF;
+    let e = decode_snapshot::<i32, i32, i32>(&corrupt).unwrap_err();
+    assert!(e.to_string().contains("checksum"));
+
+    let mut newer = bytes.clone();
+    newer[8] = 2;
+    let e = decode_snapshot::<i32, i32, i32>(&newer).unwrap_err();
+    assert!(e.to_string().contains("newer"));
+
+    let e = decode_snapshot::<i32, i64, i32>(&bytes).unwrap_err();
+    assert!(e.to_string().contains("'i32 i32 i32', not 'i32 i64 i32'"));
+    assert!(decode_snapshot::<String, i32, i32>(&bytes).is_err());
+
+    assert!(decode_snapshot::<i32, i32, i32>(b"graph D {}").is_err());
+
+    // a type description that does not fit the header is refused when writing.
+    let mut long: Graph<Verbose, i32, i32> = Graph::new();
+    long.add_node(1, Verbose);
+    let e = encode_snapshot(&long, None).unwrap_err();
+    assert!(e.to_string().contains("255"));
+}
+
+#[derive(Debug)]
+struct Verbose;
+
+impl SnapshotValue for Verbose {
+    const CODEC: &'static str = "a codec name so long that the node data, edge data and ids \
+        no longer fit the single length byte of the snapshot header, which holds at most 255 \
+        bytes; writing it anyway would leave a header that reads back as something else \
+        entirely, with the payload length taken from the middle of this sentence";
+    fn encode(&self, _: &mut Vec<u8>) {}
+    fn decode(_: &mut ByteReader) -> Result<Self, FormatError> {
+        Ok(Verbose)
+    }
+}
+
+#[test]
+fn viz_dot_positions_and_bends() {
+    let mut g: Graph<i32, i32, i32> = Graph::new();
+    g.add_node(1, 5);
+    g.add_node(2, 6);
+    g.add_edge(3, 1, 2, 7).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    write_viz_dot(&g, None, None, &mut out).unwrap();
+    let plain = String::from_utf8(out).unwrap();
+    assert!(plain.contains("    1[label=\"1 \\nData 5\"];\r\n"));
+    assert!(plain.contains("    1 -- 2 [label=7, id=3];\r\n"));
+
+    let positions: HashMap<i32, Vect> = [(1, Vect::new(0., 0., 0.)), (2, Vect::new(2., 4., 0.))]
+        .into_iter()
+        .collect();
+    let bends: HashMap<i32, Vec<Vect>> = [(3, vec![Vect::new(1., 2., 0.)])].into_iter().collect();
+    let mut out: Vec<u8> = Vec::new();
+    write_viz_dot(&g, Some(&positions), Some(&bends), &mut out).unwrap();
+    let placed = String::from_utf8(out).unwrap();
+    assert!(placed.contains("    2[label=\"2 \\nData 6\", pos=\"2,-4!\"];"));
+    assert!(placed.contains("id=3, pos=\"0,0 0,0 1,-2 1,-2 1,-2 2,-4 2,-4\"];"));
+
+    // the positioned file still reads back with its edges.
+    let path = std::env::temp_dir().join(format!("viz_dot_round_trip_{}.dot", std::process::id()));
+    let path = path.to_str().unwrap();
+    save_viz_dot(&g, Some(&positions), Some(&bends), path).unwrap();
+    let back = from_viz_dot::<i32, i32, i32>(path);
+    let _ = std::fs::remove_file(path);
+    assert_eq!(g.nodes, back.nodes);
+    assert_eq!(g.edges, back.edges);
+}